@@ -0,0 +1,258 @@
+use proc_macro2::Span;
+use quote::{format_ident, quote};
+use syn::{
+    Attribute, DeriveInput, Error, Field, FieldsNamed, Ident, Lit, Meta, NestedMeta, Result,
+};
+
+/// Name under which a field is stored in BSON, following the serde attributes.
+pub struct FieldName<'a> {
+    pub field: &'a Field,
+    pub key: String,
+}
+
+/// Lists the fields serialized by serde along with their BSON keys.
+pub fn field_names<'a>(input: &DeriveInput, fields: &'a FieldsNamed) -> Vec<FieldName<'a>> {
+    let rule = serde_str_option(&input.attrs, "rename_all");
+
+    let mut result = Vec::new();
+    for field in &fields.named {
+        if serde_flag(&field.attrs, "skip")
+            || serde_flag(&field.attrs, "skip_serializing")
+            || serde_flag(&field.attrs, "flatten")
+        {
+            continue;
+        }
+
+        let key = match serde_str_option(&field.attrs, "rename") {
+            Some(k) => k,
+            None => {
+                let name = field.ident.as_ref().unwrap().to_string();
+                let name = name.trim_start_matches("r#");
+                match &rule {
+                    Some(r) => rename(r, name),
+                    None => name.to_owned(),
+                }
+            }
+        };
+        result.push(FieldName { field, key });
+    }
+    result
+}
+
+/// Maps a struct field name to its BSON key, passing other names through unchanged.
+pub fn field_key(names: &[FieldName], field: &str) -> String {
+    names
+        .iter()
+        .find(|n| n.field.ident.as_ref().unwrap() == field)
+        .map(|n| n.key.clone())
+        .unwrap_or_else(|| field.to_owned())
+}
+
+/// Generates the `{Model}Fields` struct holding the BSON key of every field as a constant,
+/// rejecting fields whose constants would have the same name.
+pub fn field_constants(
+    input: &DeriveInput,
+    names: &[FieldName],
+) -> Result<proc_macro2::TokenStream> {
+    let ident = format_ident!("{}Fields", input.ident);
+    let doc = format!("BSON keys of the fields of [`{}`].", input.ident);
+
+    let mut consts = Vec::with_capacity(names.len());
+    let mut seen: Vec<(Ident, &Ident)> = Vec::with_capacity(names.len());
+    for n in names {
+        let field = n.field.ident.as_ref().unwrap();
+        let const_ident = field_constant_ident(&field.to_string(), Span::call_site());
+        if let Some((_, other)) = seen.iter().find(|(c, _)| *c == const_ident) {
+            return Err(Error::new_spanned(
+                field,
+                format!(
+                    "fields `{}` and `{}` would both have the constant `{}` in `{}`",
+                    other, field, const_ident, ident,
+                ),
+            ));
+        }
+        seen.push((const_ident.clone(), field));
+
+        let key = &n.key;
+        consts.push(quote! {
+            pub const #const_ident: &'static str = #key;
+        });
+    }
+
+    Ok(quote! {
+        #[doc = #doc]
+        pub struct #ident;
+
+        #[allow(dead_code)]
+        impl #ident {
+            #(#consts)*
+        }
+    })
+}
+
+/// Name of the constant of the `{Model}Fields` struct holding the BSON key of a field.
+pub fn field_constant_ident(field: &str, span: Span) -> Ident {
+    let name = field.trim_start_matches("r#").trim_start_matches('_');
+    Ident::new(&name.to_ascii_uppercase(), span)
+}
+
+/// Generates the `{Model}Filter` builder returned by `Schema::filter`.
+pub fn field_filters(input: &DeriveInput, names: &[FieldName]) -> proc_macro2::TokenStream {
+    let model = &input.ident;
+    let ident = format_ident!("{}Filter", model);
+    let doc = format!("Typed filter builder for [`{}`].", model);
+
+    let methods = names.iter().map(|n| {
+        let field_ident = n.field.ident.as_ref().unwrap();
+        let ty = &n.field.ty;
+        let key = &n.key;
+        quote! {
+            pub fn #field_ident(self) -> ::bongo::FieldFilter<#model, #ty> {
+                ::bongo::FieldFilter::new(#key)
+            }
+        }
+    });
+
+    quote! {
+        #[doc = #doc]
+        pub struct #ident;
+
+        #[allow(dead_code)]
+        impl #ident {
+            #(#methods)*
+        }
+    }
+}
+
+/// Generates the `{Model}Update` builder returned by `Schema::update`.
+pub fn field_updates(input: &DeriveInput, names: &[FieldName]) -> proc_macro2::TokenStream {
+    let model = &input.ident;
+    let ident = format_ident!("{}Update", model);
+    let doc = format!("Typed update builder for [`{}`].", model);
+
+    let methods = names.iter().filter(|n| n.key != "_id").map(|n| {
+        let field_ident = n.field.ident.as_ref().unwrap();
+        let ty = &n.field.ty;
+        let key = &n.key;
+        quote! {
+            pub fn #field_ident(self) -> ::bongo::FieldUpdate<#model, #ty, #ident> {
+                ::bongo::FieldUpdate::new(self.0, #key)
+            }
+        }
+    });
+
+    quote! {
+        #[doc = #doc]
+        pub struct #ident(::bongo::Update<#model>);
+
+        #[allow(dead_code)]
+        impl #ident {
+            #(#methods)*
+        }
+
+        impl ::std::convert::From<::bongo::Update<#model>> for #ident {
+            fn from(update: ::bongo::Update<#model>) -> Self {
+                #ident(update)
+            }
+        }
+        impl ::std::convert::From<#ident> for ::bongo::Update<#model> {
+            fn from(update: #ident) -> Self {
+                update.0
+            }
+        }
+        impl ::bongo::IntoUpdate<#model> for #ident {
+            fn into_update(
+                self,
+            ) -> ::bongo::Result<::bongo::re_exports::mongodb::options::UpdateModifications> {
+                ::bongo::IntoUpdate::<#model>::into_update(self.0)
+            }
+        }
+
+        impl ::std::default::Default for #ident {
+            fn default() -> Self {
+                #ident(::bongo::Update::new())
+            }
+        }
+    }
+}
+
+/// Applies a serde `rename_all` rule to a snake case field name, leaving unknown rules for serde
+/// to report.
+fn rename(rule: &str, name: &str) -> String {
+    match rule {
+        "lowercase" | "snake_case" => name.to_owned(),
+        "UPPERCASE" | "SCREAMING_SNAKE_CASE" => name.to_ascii_uppercase(),
+        "PascalCase" => pascal_case(name),
+        "camelCase" => {
+            let pascal = pascal_case(name);
+            match pascal.chars().next() {
+                Some(c) => format!("{}{}", c.to_ascii_lowercase(), &pascal[c.len_utf8()..]),
+                None => pascal,
+            }
+        }
+        "kebab-case" => name.replace('_', "-"),
+        "SCREAMING-KEBAB-CASE" => name.to_ascii_uppercase().replace('_', "-"),
+        _ => name.to_owned(),
+    }
+}
+
+fn pascal_case(name: &str) -> String {
+    let mut result = String::with_capacity(name.len());
+    let mut capitalize = true;
+    for c in name.chars() {
+        if c == '_' {
+            capitalize = true;
+        } else if capitalize {
+            result.push(c.to_ascii_uppercase());
+            capitalize = false;
+        } else {
+            result.push(c);
+        }
+    }
+    result
+}
+
+fn serde_metas(attrs: &[Attribute]) -> Vec<NestedMeta> {
+    let mut result = Vec::new();
+    for attr in attrs {
+        if !attr.path.is_ident("serde") {
+            continue;
+        }
+        if let Ok(Meta::List(l)) = attr.parse_meta() {
+            result.extend(l.nested);
+        }
+    }
+    result
+}
+
+/// Reads `name = "..."` or the serialization side of `name(serialize = "...")`.
+fn serde_str_option(attrs: &[Attribute], name: &str) -> Option<String> {
+    let mut result = None;
+    for opt in serde_metas(attrs) {
+        match opt {
+            NestedMeta::Meta(Meta::NameValue(nv)) if nv.path.is_ident(name) => {
+                if let Lit::Str(s) = nv.lit {
+                    result = Some(s.value());
+                }
+            }
+            NestedMeta::Meta(Meta::List(ml)) if ml.path.is_ident(name) => {
+                for opt in ml.nested {
+                    if let NestedMeta::Meta(Meta::NameValue(nv)) = opt {
+                        if let (true, Lit::Str(s)) = (nv.path.is_ident("serialize"), nv.lit) {
+                            result = Some(s.value());
+                        }
+                    }
+                }
+            }
+            _ => continue,
+        }
+    }
+    result
+}
+
+fn serde_flag(attrs: &[Attribute], name: &str) -> bool {
+    serde_metas(attrs).iter().any(|opt| match opt {
+        NestedMeta::Meta(Meta::Path(p)) => p.is_ident(name),
+        _ => false,
+    })
+}
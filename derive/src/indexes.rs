@@ -0,0 +1,356 @@
+use crate::{
+    attr_is_bongo,
+    fields::{field_key, FieldName},
+    parse_attr,
+};
+use proc_macro2::Span;
+use quote::quote;
+use syn::{spanned::Spanned, DeriveInput, Error, Lit, Meta, MetaList, NestedMeta, Path, Result};
+
+struct IndexSpec {
+    /// Where the index is declared, to report duplicates.
+    span: Span,
+    keys: Vec<(String, i32)>,
+    name: Option<String>,
+    unique: bool,
+    sparse: bool,
+    ttl: Option<i64>,
+    partial_filter: Option<Path>,
+    collation: Vec<(String, Lit)>,
+}
+
+impl IndexSpec {
+    fn new(span: Span, keys: Vec<(String, i32)>) -> Self {
+        Self {
+            span,
+            keys,
+            name: None,
+            unique: false,
+            sparse: false,
+            ttl: None,
+            partial_filter: None,
+            collation: Vec::new(),
+        }
+    }
+
+    /// Name of the index, defaulting to the one the server gives it.
+    fn name(&self) -> String {
+        match &self.name {
+            Some(name) => name.clone(),
+            None => self
+                .keys
+                .iter()
+                .map(|(k, d)| format!("{}_{}", k, d))
+                .collect::<Vec<_>>()
+                .join("_"),
+        }
+    }
+
+    fn to_tokens(&self) -> proc_macro2::TokenStream {
+        let keys = self
+            .keys
+            .iter()
+            .map(|(k, d)| quote! { keys.insert(#k, #d); });
+
+        let mut options = Vec::new();
+        if let Some(name) = &self.name {
+            options.push(quote! { options.insert("name", #name); });
+        }
+        if self.unique {
+            options.push(quote! { options.insert("unique", true); });
+        }
+        if self.sparse {
+            options.push(quote! { options.insert("sparse", true); });
+        }
+        if let Some(ttl) = self.ttl {
+            options.push(quote! { options.insert("expireAfterSeconds", #ttl); });
+        }
+        if let Some(path) = &self.partial_filter {
+            options.push(quote! {
+                options.insert(
+                    "partialFilterExpression",
+                    ::std::convert::Into::<::bongo::re_exports::bson::Document>::into(#path()),
+                );
+            });
+        }
+        if !self.collation.is_empty() {
+            let collation = self
+                .collation
+                .iter()
+                .map(|(k, v)| quote! { collation.insert(#k, #v); });
+            options.push(quote! {
+                options.insert("collation", {
+                    let mut collation = ::bongo::re_exports::bson::Document::new();
+                    #(#collation)*
+                    collation
+                });
+            });
+        }
+
+        quote! {
+            ::bongo::index_model(
+                {
+                    let mut keys = ::bongo::re_exports::bson::Document::new();
+                    #(#keys)*
+                    keys
+                },
+                {
+                    #[allow(unused_mut)]
+                    let mut options = ::bongo::re_exports::bson::Document::new();
+                    #(#options)*
+                    options
+                },
+            )
+        }
+    }
+}
+
+/// Generates the expressions building the indexes declared on the struct and its fields.
+pub fn indexes(input: &DeriveInput, names: &[FieldName]) -> Result<Vec<proc_macro2::TokenStream>> {
+    let mut specs = Vec::new();
+
+    for name in names {
+        // The `index`, `unique` and `ttl` flags of a field all describe its single field index.
+        let mut flagged: Option<IndexSpec> = None;
+        for attr in &name.field.attrs {
+            if !attr_is_bongo(attr) {
+                continue;
+            }
+
+            let attr = parse_attr(attr)?;
+            for opt in attr.nested {
+                let key = vec![(name.key.clone(), 1)];
+                let span = opt.span();
+                match opt {
+                    NestedMeta::Meta(Meta::Path(p)) if p.is_ident("index") => {
+                        flagged.get_or_insert_with(|| IndexSpec::new(span, key));
+                    }
+                    NestedMeta::Meta(Meta::Path(p)) if p.is_ident("unique") => {
+                        flagged
+                            .get_or_insert_with(|| IndexSpec::new(span, key))
+                            .unique = true;
+                    }
+                    NestedMeta::Meta(Meta::NameValue(nv)) if nv.path.is_ident("ttl") => {
+                        flagged.get_or_insert_with(|| IndexSpec::new(span, key)).ttl =
+                            Some(ttl(&nv.lit)?);
+                    }
+                    NestedMeta::Meta(Meta::List(ml)) if ml.path.is_ident("index") => {
+                        specs.push(index(&ml, key, names)?)
+                    }
+                    _ => continue,
+                }
+            }
+        }
+        specs.extend(flagged);
+    }
+
+    for attr in &input.attrs {
+        if !attr_is_bongo(attr) {
+            continue;
+        }
+
+        let attr = parse_attr(attr)?;
+        for opt in attr.nested {
+            match opt {
+                NestedMeta::Meta(Meta::List(ml)) if ml.path.is_ident("index") => {
+                    specs.push(index(&ml, Vec::new(), names)?)
+                }
+                _ => continue,
+            }
+        }
+    }
+
+    let mut declared: Vec<String> = Vec::with_capacity(specs.len());
+    for spec in &specs {
+        let name = spec.name();
+        if declared.contains(&name) {
+            return Err(Error::new(
+                spec.span,
+                format!(
+                    "index {} is declared twice, merge its options or give one of them a name",
+                    name
+                ),
+            ));
+        }
+        declared.push(name);
+    }
+
+    Ok(specs.iter().map(IndexSpec::to_tokens).collect())
+}
+
+/// Parses the options of an `index(...)` attribute, `keys` being the annotated field if any.
+fn index(ml: &MetaList, keys: Vec<(String, i32)>, names: &[FieldName]) -> Result<IndexSpec> {
+    let mut spec = IndexSpec::new(ml.span(), keys);
+    for opt in &ml.nested {
+        match opt {
+            NestedMeta::Meta(Meta::List(l)) if l.path.is_ident("fields") => {
+                for field in &l.nested {
+                    match field {
+                        NestedMeta::Lit(Lit::Str(s)) => {
+                            spec.keys.push(index_key(&s.value(), names))
+                        }
+                        _ => {
+                            return Err(Error::new_spanned(
+                                field,
+                                "index fields should be string literals",
+                            ))
+                        }
+                    }
+                }
+            }
+            NestedMeta::Meta(Meta::Path(p)) if p.is_ident("unique") => spec.unique = true,
+            NestedMeta::Meta(Meta::Path(p)) if p.is_ident("sparse") => spec.sparse = true,
+            NestedMeta::Meta(Meta::NameValue(nv)) if nv.path.is_ident("name") => match &nv.lit {
+                Lit::Str(s) => spec.name = Some(s.value()),
+                lit => {
+                    return Err(Error::new_spanned(
+                        lit,
+                        "index name should be a string literal",
+                    ))
+                }
+            },
+            NestedMeta::Meta(Meta::NameValue(nv)) if nv.path.is_ident("ttl") => {
+                spec.ttl = Some(ttl(&nv.lit)?)
+            }
+            NestedMeta::Meta(Meta::NameValue(nv)) if nv.path.is_ident("partial_filter") => {
+                match &nv.lit {
+                    Lit::Str(s) => match s.parse() {
+                        Ok(p) => spec.partial_filter = Some(p),
+                        Err(_) => {
+                            return Err(Error::new_spanned(
+                                s,
+                                "partial filter should be a function path",
+                            ))
+                        }
+                    },
+                    lit => {
+                        return Err(Error::new_spanned(
+                            lit,
+                            "partial filter should be a function path as a string literal",
+                        ))
+                    }
+                }
+            }
+            NestedMeta::Meta(Meta::List(l)) if l.path.is_ident("collation") => {
+                for opt in &l.nested {
+                    match opt {
+                        NestedMeta::Meta(Meta::NameValue(nv)) => {
+                            let key = match nv.path.get_ident() {
+                                Some(i) => collation_key(&i.to_string()),
+                                None => {
+                                    return Err(Error::new_spanned(
+                                        &nv.path,
+                                        "invalid collation option",
+                                    ))
+                                }
+                            };
+                            spec.collation.push((key, nv.lit.clone()));
+                        }
+                        opt => {
+                            return Err(Error::new_spanned(
+                                opt,
+                                "collation options should be name-value pairs",
+                            ))
+                        }
+                    }
+                }
+            }
+            opt => {
+                return Err(Error::new_spanned(
+                    opt,
+                    "invalid index option, expected one of fields, unique, sparse, name, ttl, \
+                     partial_filter or collation",
+                ))
+            }
+        }
+    }
+    if spec.keys.is_empty() {
+        return Err(Error::new_spanned(
+            ml,
+            "index should list at least one field with `fields(...)`",
+        ));
+    }
+    Ok(spec)
+}
+
+/// Resolves `"field"` or `"-field"` to a BSON key and direction, mapping struct field names to
+/// their serialized keys and passing other paths through.
+fn index_key(field: &str, names: &[FieldName]) -> (String, i32) {
+    let (field, direction) = match field.strip_prefix('-') {
+        Some(f) => (f, -1),
+        None => (field, 1),
+    };
+    (field_key(names, field), direction)
+}
+
+/// Converts a collation option to its camel case server name.
+fn collation_key(name: &str) -> String {
+    let mut result = String::with_capacity(name.len());
+    let mut capitalize = false;
+    for c in name.chars() {
+        if c == '_' {
+            capitalize = true;
+        } else if capitalize {
+            result.push(c.to_ascii_uppercase());
+            capitalize = false;
+        } else {
+            result.push(c);
+        }
+    }
+    result
+}
+
+/// Parses a TTL like `"30d"` or `"1h30m"` to seconds, also accepting plain integer seconds.
+fn ttl(lit: &Lit) -> Result<i64> {
+    let s = match lit {
+        Lit::Int(i) => {
+            return match i.base10_parse::<i64>()? {
+                ttl if ttl >= 0 => Ok(ttl),
+                _ => Err(Error::new_spanned(lit, "ttl can't be negative")),
+            }
+        }
+        Lit::Str(s) => s.value(),
+        _ => {
+            return Err(Error::new_spanned(
+                lit,
+                "ttl should be a duration string like \"30d\"",
+            ))
+        }
+    };
+
+    let mut total = 0;
+    let mut number = String::new();
+    for c in s.chars() {
+        if c.is_ascii_digit() {
+            number.push(c);
+            continue;
+        }
+
+        let unit = match c {
+            's' => 1,
+            'm' => 60,
+            'h' => 60 * 60,
+            'd' => 24 * 60 * 60,
+            'w' => 7 * 24 * 60 * 60,
+            _ => {
+                return Err(Error::new_spanned(
+                    lit,
+                    format!("invalid ttl unit {}, expected one of s, m, h, d or w", c),
+                ))
+            }
+        };
+        let value: i64 = match number.parse() {
+            Ok(v) => v,
+            Err(_) => return Err(Error::new_spanned(lit, format!("invalid ttl {}", s))),
+        };
+        total = match value.checked_mul(unit).and_then(|v| v.checked_add(total)) {
+            Some(t) => t,
+            None => return Err(Error::new_spanned(lit, format!("ttl {} is too long", s))),
+        };
+        number.clear();
+    }
+    if !number.is_empty() || s.is_empty() {
+        return Err(Error::new_spanned(lit, format!("invalid ttl {}", s)));
+    }
+    Ok(total)
+}
@@ -1,124 +1,248 @@
 extern crate proc_macro;
 
+mod fields;
+mod indexes;
+mod options;
+mod referenced_by;
+mod soft_delete;
+mod timestamps;
+mod validation;
+mod version;
+
+use crate::{
+    fields::{field_constants, field_filters, field_names, field_updates, FieldName},
+    indexes::indexes,
+    options::check_options,
+    referenced_by::{referenced_by, InverseRelations},
+    soft_delete::soft_delete,
+    timestamps::timestamps,
+    validation::{validations, Validations},
+    version::version,
+};
 use proc_macro::TokenStream;
 use quote::{format_ident, quote};
 use syn::{
-    parse_macro_input, Attribute, Data, DeriveInput, Field, Fields, FieldsNamed, Ident, Lit, Meta,
-    MetaList, NestedMeta, Path,
+    parse_macro_input, Attribute, Data, DeriveInput, Error, Field, Fields, FieldsNamed,
+    GenericArgument, Ident, Lit, Meta, MetaList, NestedMeta, Path, PathArguments, Result, Type,
 };
 
 #[proc_macro_derive(BlockingModel, attributes(bongo))]
 pub fn blocking_model(input: TokenStream) -> TokenStream {
     let input = parse_macro_input!(input as DeriveInput);
-    TokenStream::from(blocking_model_impl(input).0)
+    TokenStream::from(match blocking_model_impl(&input, false) {
+        Ok((expanded, ..)) => expanded,
+        Err(e) => e.to_compile_error(),
+    })
 }
 
 #[proc_macro_derive(Model, attributes(bongo))]
 pub fn model(input: TokenStream) -> TokenStream {
     let input = parse_macro_input!(input as DeriveInput);
+    TokenStream::from(match model_impl(&input) {
+        Ok(expanded) => expanded,
+        Err(e) => e.to_compile_error(),
+    })
+}
 
-    let ident = input.ident.clone();
+fn model_impl(input: &DeriveInput) -> Result<proc_macro2::TokenStream> {
+    let ident = &input.ident;
+    let hooks = if struct_flag(input, "hooks")? {
+        None
+    } else {
+        Some(quote! { impl ::bongo::Hooks for #ident {} })
+    };
 
-    let (blocking_impl, relations) = blocking_model_impl(input);
+    let (blocking_impl, relations, validations) = blocking_model_impl(input, true)?;
     let Relations {
         getters, checks, ..
     } = relations;
+    let validations = validations.checks;
 
-    let expanded = quote! {
+    Ok(quote! {
         #blocking_impl
 
         #[::bongo::re_exports::async_trait::async_trait]
         impl ::bongo::Model for #ident {
             async fn check_relations(&self) -> ::bongo::Result<()> {
-                use ::bongo::{
-                    re_exports::{bson::{bson, doc}, tokio::task},
-                    BlockingModel, Error,
-                };
-
-                #(#checks)*
+                #checks
                 Ok(())
             }
+
+            #[allow(unused_variables)]
+            async fn collect_validation_errors_async(
+                &self,
+                errors: &mut ::bongo::ValidationErrors,
+            ) {
+                #(#validations)*
+            }
         }
 
+        #hooks
+
         impl #ident {
             #(#getters)*
         }
-    };
-    TokenStream::from(expanded)
+    })
 }
 
-fn blocking_model_impl(input: DeriveInput) -> (proc_macro2::TokenStream, Relations) {
+fn blocking_model_impl(
+    input: &DeriveInput,
+    asynchronous: bool,
+) -> Result<(proc_macro2::TokenStream, Relations, Validations)> {
     let ident = &input.ident;
     let fields = match &input.data {
         Data::Struct(s) => match &s.fields {
             Fields::Named(f) => f,
-            _ => panic!("bongo only supports named fields"),
+            f => return Err(Error::new_spanned(f, "bongo only supports named fields")),
         },
-        _ => panic!("bongo only supports structs"),
+        _ => return Err(Error::new_spanned(ident, "bongo only supports structs")),
     };
-
-    let collection = collection_name(&input);
-
-    let id = id_field(fields);
+    check_options(input, fields, asynchronous)?;
+
+    let collection = collection_name(input)?;
+    let connection = connection_name(input)?;
+    let fields_ident = format_ident!("{}Fields", ident);
+    let filter_ident = format_ident!("{}Filter", ident);
+    let update_ident = format_ident!("{}Update", ident);
+    let relations_ident = format_ident!("{}Relations", ident);
+
+    let names = field_names(input, fields);
+    let constants = field_constants(input, &names)?;
+    let filters = field_filters(input, &names);
+    let updates = field_updates(input, &names);
+    let indexes = indexes(input, &names)?;
+    let validations = validations(input, &names)?;
+    let timestamps = timestamps(input, &names)?;
+    let version = version(&names)?;
+    let soft_delete = soft_delete(input, &names)?;
+
+    let id = id_field(input, &names)?;
     let id_ty = &id.ty;
     let id_ident = id.ident.as_ref().unwrap();
 
-    let relations = relations(fields);
+    let relations = relations(input, fields)?;
     let Relations {
         getters_sync,
         checks_sync,
+        accessors,
         ..
     } = &relations;
+    let checks_validation = &validations.checks_sync;
+    let hooks = if struct_flag(input, "hooks")? {
+        None
+    } else {
+        Some(quote! { impl ::bongo::BlockingHooks for #ident {} })
+    };
 
-    (
+    Ok((
         quote! {
             impl ::bongo::BlockingModel for #ident {
                 type Id = #id_ty;
 
-                fn collection() -> ::bongo::Result<&'static ::bongo::re_exports::mongodb::Collection> {
-                    use ::bongo::re_exports::{
-                        mongodb::Collection,
-                        once_cell::sync::OnceCell,
-                    };
-
-                    static COLLECTION: OnceCell<Collection> = OnceCell::new();
-
-                    if let Some(c) = COLLECTION.get() {
-                        return Ok(c);
-                    }
+                fn database() -> ::bongo::Result<::bongo::re_exports::mongodb::Database> {
+                    ::bongo::database_named(#connection)
+                }
+                fn collection() -> ::bongo::Result<::bongo::re_exports::mongodb::Collection> {
+                    static COLLECTION: ::bongo::CollectionCache = ::bongo::CollectionCache::new();
+                    COLLECTION.get(#connection, #collection)
+                }
 
-                    COLLECTION.set(::bongo::database()?.collection(#collection)).unwrap();
-                    Ok(COLLECTION.get().unwrap())
+                fn indexes() -> Vec<::bongo::re_exports::mongodb::options::IndexModel> {
+                    vec![#(#indexes),*]
                 }
 
                 fn id(&self) -> Self::Id {
                     self.#id_ident.clone()
                 }
 
-                fn check_relations_sync(&self) -> ::bongo::Result<()> {
-                    use ::bongo::{re_exports::bson::{bson, doc}, BlockingModel, Error};
+                #timestamps
+                #version
+                #soft_delete
+
+                #[allow(unused_variables)]
+                fn collect_validation_errors(&self, errors: &mut ::bongo::ValidationErrors) {
+                    #(#checks_validation)*
+                }
 
+                fn check_relations_sync(&self) -> ::bongo::Result<()> {
                     #(#checks_sync)*
                     Ok(())
                 }
             }
 
+            #hooks
+
             impl #ident {
                 #(#getters_sync)*
             }
+
+            // The builders are only reachable through `Schema`, so they can't clash with the
+            // items of the module declaring the model.
+            const _: () = {
+                #constants
+                #filters
+                #accessors
+                #updates
+
+                impl ::bongo::Schema for #ident {
+                    type Fields = #fields_ident;
+                    type Filter = #filter_ident;
+                    type Update = #update_ident;
+                    type Relations = #relations_ident;
+
+                    fn filter() -> Self::Filter {
+                        #filter_ident
+                    }
+                    fn update() -> Self::Update {
+                        ::std::default::Default::default()
+                    }
+                    fn relations() -> Self::Relations {
+                        #relations_ident
+                    }
+                }
+            };
+
+            ::bongo::re_exports::inventory::submit! {
+                ::bongo::ModelInfo {
+                    type_name: concat!(module_path!(), "::", stringify!(#ident)),
+                    connection: #connection,
+                    collection: #collection,
+                    indexes: <#ident as ::bongo::BlockingModel>::indexes,
+                    sync_indexes: <#ident as ::bongo::BlockingModel>::sync_indexes_sync,
+                }
+            }
         },
         relations,
-    )
+        validations,
+    ))
+}
+
+/// Returns `T` if `ty` is written as `Option<T>`.
+fn option_inner(ty: &Type) -> Option<&Type> {
+    let segment = match ty {
+        Type::Path(p) if p.qself.is_none() => p.path.segments.last()?,
+        _ => return None,
+    };
+    if segment.ident != "Option" {
+        return None;
+    }
+    match &segment.arguments {
+        PathArguments::AngleBracketed(a) if a.args.len() == 1 => match a.args.first()? {
+            GenericArgument::Type(t) => Some(t),
+            _ => None,
+        },
+        _ => None,
+    }
 }
 
 fn attr_is_bongo(attr: &Attribute) -> bool {
     attr.path.is_ident("bongo")
 }
 
-fn parse_attr(attr: &Attribute) -> MetaList {
-    match attr.parse_meta() {
-        Ok(Meta::List(l)) => l,
-        _ => panic!("invalid attribute syntax"),
+fn parse_attr(attr: &Attribute) -> Result<MetaList> {
+    match attr.parse_meta()? {
+        Meta::List(l) => Ok(l),
+        m => Err(Error::new_spanned(m, "expected `#[bongo(...)]`")),
     }
 }
 
@@ -127,7 +251,7 @@ fn camel_case(s: &str) -> String {
     format!("{}{}s", first_char, &s[1..])
 }
 
-fn collection_name(input: &DeriveInput) -> String {
+fn struct_str_option(input: &DeriveInput, name: &str) -> Result<Option<String>> {
     let attrs = &input.attrs;
     let mut result = None;
     for attr in attrs {
@@ -135,81 +259,87 @@ fn collection_name(input: &DeriveInput) -> String {
             continue;
         }
 
-        let attr = parse_attr(attr);
+        let attr = parse_attr(attr)?;
         for opt in attr.nested {
             let nv = match opt {
                 NestedMeta::Meta(Meta::NameValue(nv)) => nv,
                 _ => continue,
             };
-            if nv.path.is_ident("collection") {
+            if nv.path.is_ident(name) {
                 match nv.lit {
                     Lit::Str(s) => result = Some(s.value()),
-                    _ => panic!("collection name should be a string literal"),
+                    lit => {
+                        return Err(Error::new_spanned(
+                            lit,
+                            format!("{} name should be a string literal", name),
+                        ))
+                    }
                 }
             }
         }
     }
-    result.unwrap_or_else(|| camel_case(&input.ident.to_string()))
+    Ok(result)
 }
 
-fn id_field(fields: &FieldsNamed) -> &Field {
-    for field in &fields.named {
-        if &field.ident.as_ref().unwrap().to_string() == "_id" {
-            return field;
+fn struct_flag(input: &DeriveInput, name: &str) -> Result<bool> {
+    for attr in input.attrs.iter().filter(|a| attr_is_bongo(a)) {
+        let found = parse_attr(attr)?
+            .nested
+            .iter()
+            .any(|opt| matches!(opt, NestedMeta::Meta(Meta::Path(p)) if p.is_ident(name)));
+        if found {
+            return Ok(true);
         }
-        for attr in &field.attrs {
-            if !attr.path.is_ident("serde") {
-                continue;
-            }
+    }
+    Ok(false)
+}
 
-            let attr = match attr.parse_meta() {
-                Ok(Meta::List(l)) => l,
-                _ => continue,
-            };
-            for opt in attr.nested {
-                let nv = match opt {
-                    NestedMeta::Meta(Meta::NameValue(nv)) => nv,
-                    _ => continue,
-                };
-                if nv.path.is_ident("rename") {
-                    match nv.lit {
-                        Lit::Str(s) => {
-                            if &s.value() == "_id" {
-                                return field;
-                            }
-                        }
-                        _ => continue,
-                    }
-                }
-            }
-        }
+fn collection_name(input: &DeriveInput) -> Result<String> {
+    Ok(struct_str_option(input, "collection")?
+        .unwrap_or_else(|| camel_case(&input.ident.to_string())))
+}
+
+fn connection_name(input: &DeriveInput) -> Result<String> {
+    Ok(struct_str_option(input, "connection")?.unwrap_or_else(|| "default".to_owned()))
+}
+
+fn id_field<'a>(input: &DeriveInput, names: &[FieldName<'a>]) -> Result<&'a Field> {
+    match names.iter().find(|n| n.key == "_id") {
+        Some(n) => Ok(n.field),
+        None => Err(Error::new_spanned(
+            &input.ident,
+            "no `_id` field on struct, add one or rename a field with `#[serde(rename = \"_id\")]`",
+        )),
     }
-    panic!("no _id field on struct");
 }
 
 struct Relations {
     getters_sync: Vec<proc_macro2::TokenStream>,
     getters: Vec<proc_macro2::TokenStream>,
     checks_sync: Vec<proc_macro2::TokenStream>,
-    checks: Vec<proc_macro2::TokenStream>,
+    checks: Option<proc_macro2::TokenStream>,
+    accessors: proc_macro2::TokenStream,
 }
 
-fn relations(fields: &FieldsNamed) -> Relations {
-    let mut getters_sync = Vec::new();
-    let mut getters = Vec::new();
-    let mut checks_sync = Vec::new();
-    let mut checks = Vec::new();
+/// Generates the relation getters, and checks collecting the references of every field to a
+/// target model so each model is queried once.
+fn relations(input: &DeriveInput, fields: &FieldsNamed) -> Result<Relations> {
+    let owner = &input.ident;
+    let InverseRelations {
+        mut getters_sync,
+        mut getters,
+    } = referenced_by(input)?;
+    let mut accessors = Vec::new();
+    let mut targets: Vec<(Path, Vec<proc_macro2::TokenStream>)> = Vec::new();
 
     for field in &fields.named {
-        let ident = field.ident.as_ref().unwrap();
-
         let attrs = &field.attrs;
         for attr in attrs {
             if !attr_is_bongo(attr) {
                 continue;
             }
 
-            let attr = parse_attr(attr);
+            let attr = parse_attr(attr)?;
             for opt in attr.nested {
                 let ml = match opt {
                     NestedMeta::Meta(Meta::List(ml)) => ml,
@@ -217,171 +347,278 @@ fn relations(fields: &FieldsNamed) -> Relations {
                 };
 
                 let relation = if ml.path.is_ident("has_one") {
-                    one_relation(&ml, ident)
+                    one_relation(&ml, field, owner)?
                 } else if ml.path.is_ident("has_many") {
-                    many_relation(&ml, ident)
+                    many_relation(&ml, field, owner)?
                 } else {
                     continue;
                 };
-                getters_sync.push(relation.getter_sync);
-                getters.push(relation.getter);
-                checks_sync.push(relation.check_sync);
-                checks.push(relation.check);
+                let Relation {
+                    getter_sync,
+                    getter,
+                    accessor,
+                    model,
+                    refs,
+                } = relation;
+                getters_sync.push(getter_sync);
+                getters.push(getter);
+                accessors.push(accessor);
+                match targets.iter_mut().find(|(m, _)| *m == model) {
+                    Some((_, r)) => r.push(refs),
+                    None => targets.push((model, vec![refs])),
+                }
             }
         }
     }
 
-    Relations {
+    let mut checks_sync = Vec::new();
+    let mut checks_async = Vec::new();
+    for (model, refs) in &targets {
+        checks_sync.push(quote! {
+            {
+                let mut refs = Vec::new();
+                #(#refs)*
+                ::bongo::check_related_sync::<#model>(refs)?;
+            }
+        });
+        checks_async.push(quote! {
+            {
+                let mut refs = Vec::new();
+                #(#refs)*
+                ::bongo::check_related::<#model>(refs)
+            }
+        });
+    }
+    let checks = if checks_async.is_empty() {
+        None
+    } else {
+        Some(quote! {
+            ::bongo::re_exports::futures::try_join!(#(#checks_async),*)?;
+        })
+    };
+
+    let ident = format_ident!("{}Relations", owner);
+    let doc = format!("Relations of [`{}`], to populate in bulk.", owner);
+    let accessors = quote! {
+        #[doc = #doc]
+        pub struct #ident;
+
+        #[allow(dead_code)]
+        impl #ident {
+            #(#accessors)*
+        }
+    };
+
+    Ok(Relations {
         getters_sync,
         getters,
         checks_sync,
         checks,
-    }
+        accessors,
+    })
 }
 
 struct Relation {
     getter_sync: proc_macro2::TokenStream,
     getter: proc_macro2::TokenStream,
-    check_sync: proc_macro2::TokenStream,
-    check: proc_macro2::TokenStream,
+    /// Method of the `{Model}Relations` struct returning the `::bongo::Relation`.
+    accessor: proc_macro2::TokenStream,
+    model: Path,
+    /// Pushes the referenced ids, with their display form, onto `refs`.
+    refs: proc_macro2::TokenStream,
 }
 
-fn one_relation(ml: &MetaList, ident: &Ident) -> Relation {
-    let rel = relation_info(ml, ident);
+fn one_relation(ml: &MetaList, field: &Field, owner: &Ident) -> Result<Relation> {
+    let ident = field.ident.as_ref().unwrap();
+    let rel = relation_info(ml, ident)?;
     let RelationInfo {
         model,
         sync_getter_name,
         getter_name,
     } = rel;
 
+    // An `Option<Id>` field references nothing when `None`, which the getters return as is.
+    let optional = option_inner(&field.ty).is_some();
+    let (output, found, id) = if optional {
+        (
+            quote! { Option<#model> },
+            quote! { Some(m) },
+            quote! {
+                match &self.#ident {
+                    Some(id) => id,
+                    None => return Ok(None),
+                }
+            },
+        )
+    } else {
+        (quote! { #model }, quote! { m }, quote! { &self.#ident })
+    };
+
     let getter_sync = quote! {
-        pub fn #sync_getter_name(&self) -> ::bongo::Result<#model> {
+        pub fn #sync_getter_name(&self) -> ::bongo::Result<#output> {
             use ::bongo::{BlockingModel, Error};
 
-            match #model::find_by_id_sync(self.#ident.clone())? {
-                Some(m) => Ok(m),
+            let id = #id;
+            match #model::find_by_id_sync(id.clone())? {
+                Some(m) => Ok(#found),
                 None => Err(Error::Relation(format!(
                     "referenced document with id {} doesn't exist",
-                    self.#ident,
+                    id,
                 ))),
             }
         }
     };
     let getter = quote! {
-        pub async fn #getter_name(&self) -> ::bongo::Result<#model> {
-            use ::bongo::{re_exports::tokio::task, BlockingModel, Error};
+        pub async fn #getter_name(&self) -> ::bongo::Result<#output> {
+            use ::bongo::{Error, Model};
 
-            let id = self.#ident.clone();
-            match task::spawn_blocking(move || #model::find_by_id_sync(id)).await?? {
-                Some(m) => Ok(m),
+            let id = #id;
+            match #model::find_by_id(id.clone()).await? {
+                Some(m) => Ok(#found),
                 None => Err(Error::Relation(format!(
                     "referenced document with id {} doesn't exist",
-                    self.#ident,
+                    id,
                 ))),
             }
         }
     };
-    let check_sync = quote! {
-        if #model::count_documents_sync(doc! {"_id": self.#ident.clone()})? < 1 {
-            return Err(Error::Relation(format!(
-                "referenced document with id {} doesn't exist",
-                self.#ident,
-            )));
+    let accessor = if optional {
+        quote! {
+            pub fn #ident(self) -> ::bongo::Relation<#owner, #model, Option<#model>> {
+                ::bongo::Relation::new(
+                    |m| m.#ident.iter().cloned().collect(),
+                    |m, get| m.#ident.as_ref().map(get).transpose(),
+                )
+            }
+        }
+    } else {
+        quote! {
+            pub fn #ident(self) -> ::bongo::Relation<#owner, #model, #model> {
+                ::bongo::Relation::new(|m| vec![m.#ident.clone()], |m, get| get(&m.#ident))
+            }
         }
     };
-    let check = quote! {
-        let query = doc! {"_id": self.#ident.clone()};
-        if task::spawn_blocking(move || #model::count_documents_sync(query)).await?? < 1 {
-            return Err(Error::Relation(format!(
-                "referenced document with id {} doesn't exist",
-                self.#ident,
-            )));
+    let push = quote! {
+        refs.push((id.clone().into(), id.to_string()));
+    };
+    let refs = if optional {
+        quote! {
+            if let Some(id) = &self.#ident {
+                #push
+            }
+        }
+    } else {
+        quote! {
+            {
+                let id = &self.#ident;
+                #push
+            }
         }
     };
 
-    Relation {
+    Ok(Relation {
         getter_sync,
         getter,
-        check_sync,
-        check,
-    }
+        accessor,
+        model: model.clone(),
+        refs,
+    })
 }
 
-fn many_relation(ml: &MetaList, ident: &Ident) -> Relation {
-    let rel = relation_info(ml, ident);
+fn many_relation(ml: &MetaList, field: &Field, owner: &Ident) -> Result<Relation> {
+    let ident = field.ident.as_ref().unwrap();
+    let rel = relation_info(ml, ident)?;
     let RelationInfo {
         model,
         sync_getter_name,
         getter_name,
     } = rel;
 
-    let getter_sync = quote! {
-        pub fn #sync_getter_name(&self) -> ::bongo::Result<Vec<#model>> {
-            use ::bongo::{BlockingModel, Error};
-
-            let mut result = Vec::with_capacity(self.#ident.len());
-            for id in &self.#ident {
-                match #model::find_by_id_sync(id.clone())? {
-                    Some(m) => result.push(m),
-                    None => {
-                        return Err(Error::Relation(format!(
-                            "referenced document with id {} doesn't exist",
-                            id,
-                        )));
-                    },
+    let optional = option_inner(&field.ty).is_some();
+    let (getter_sync, getter) = if optional {
+        (
+            quote! {
+                pub fn #sync_getter_name(&self) -> ::bongo::Result<Option<Vec<#model>>> {
+                    match &self.#ident {
+                        Some(ids) => ::bongo::find_related_sync::<#model>(ids).map(Some),
+                        None => Ok(None),
+                    }
                 }
-            }
-            Ok(result)
-        }
+            },
+            quote! {
+                pub async fn #getter_name(&self) -> ::bongo::Result<Option<Vec<#model>>> {
+                    match &self.#ident {
+                        Some(ids) => ::bongo::find_related::<#model>(ids.clone()).await.map(Some),
+                        None => Ok(None),
+                    }
+                }
+            },
+        )
+    } else {
+        (
+            quote! {
+                pub fn #sync_getter_name(&self) -> ::bongo::Result<Vec<#model>> {
+                    ::bongo::find_related_sync::<#model>(&self.#ident)
+                }
+            },
+            quote! {
+                pub async fn #getter_name(&self) -> ::bongo::Result<Vec<#model>> {
+                    ::bongo::find_related::<#model>(self.#ident.clone()).await
+                }
+            },
+        )
     };
-    let getter = quote! {
-        pub async fn #getter_name(&self) -> ::bongo::Result<Vec<#model>> {
-            use ::bongo::{re_exports::tokio::task, BlockingModel, Error};
-
-            let mut result = Vec::with_capacity(self.#ident.len());
-            for id in &self.#ident {
-                let move_id = id.clone();
-                match task::spawn_blocking(move || #model::find_by_id_sync(move_id)).await?? {
-                    Some(m) => result.push(m),
-                    None => {
-                        return Err(Error::Relation(format!(
-                            "referenced document with id {} doesn't exist",
-                            id,
-                        )));
+    let accessor = if optional {
+        quote! {
+            pub fn #ident(self) -> ::bongo::Relation<#owner, #model, Option<Vec<#model>>> {
+                ::bongo::Relation::new(
+                    |m| m.#ident.iter().flatten().cloned().collect(),
+                    |m, get| {
+                        m.#ident
+                            .as_ref()
+                            .map(|ids| ids.iter().map(get).collect::<::bongo::Result<_>>())
+                            .transpose()
                     },
-                }
+                )
             }
-            Ok(result)
         }
-    };
-    let check_sync = quote! {
-        for id in &self.#ident {
-            if #model::count_documents_sync(doc! {"_id": id.clone()})? < 1 {
-                return Err(Error::Relation(format!(
-                    "referenced document with id {} doesn't exist",
-                    id,
-                )));
+    } else {
+        quote! {
+            pub fn #ident(self) -> ::bongo::Relation<#owner, #model, Vec<#model>> {
+                ::bongo::Relation::new(
+                    |m| m.#ident.clone(),
+                    |m, get| m.#ident.iter().map(get).collect(),
+                )
             }
         }
     };
-    let check = quote! {
-        for id in &self.#ident {
-            let query = doc! {"_id": id.clone()};
-            if task::spawn_blocking(move || #model::count_documents_sync(query)).await?? < 1 {
-                return Err(Error::Relation(format!(
-                    "referenced document with id {} doesn't exist",
-                    id,
-                )));
+    let push = quote! {
+        for id in ids {
+            refs.push((id.clone().into(), id.to_string()));
+        }
+    };
+    let refs = if optional {
+        quote! {
+            if let Some(ids) = &self.#ident {
+                #push
+            }
+        }
+    } else {
+        quote! {
+            {
+                let ids = &self.#ident;
+                #push
             }
         }
     };
 
-    Relation {
+    Ok(Relation {
         getter_sync,
         getter,
-        check_sync,
-        check,
-    }
+        accessor,
+        model: model.clone(),
+        refs,
+    })
 }
 
 struct RelationInfo<'a> {
@@ -390,32 +627,54 @@ struct RelationInfo<'a> {
     getter_name: Ident,
 }
 
-fn relation_info<'a, 'b>(ml: &'a MetaList, ident: &'b Ident) -> RelationInfo<'a> {
+fn relation_info<'a>(ml: &'a MetaList, ident: &Ident) -> Result<RelationInfo<'a>> {
     let nested = &ml.nested;
     let mut nested_iter = nested.iter();
 
     let model = match nested_iter.next() {
         Some(NestedMeta::Meta(Meta::Path(p))) => p,
-        _ => panic!("first argument of relation attribute must be the target type"),
-    };
-    let sync_getter_name = match nested_iter.next() {
-        Some(NestedMeta::Lit(Lit::Str(s))) => format_ident!("{}", s.value()),
-        None => format_ident!("{}_sync", ident),
-        _ => panic!(
-            "second argument of relation attribute must be the synchronous getter name as a string literal"
-        ),
-    };
-    let getter_name = match nested_iter.next() {
-        Some(NestedMeta::Lit(Lit::Str(s))) => format_ident!("{}", s.value()),
-        None => format_ident!("{}", ident),
-        _ => panic!(
-            "second argument of relation attribute must be the getter name as a string literal"
-        ),
+        Some(opt) => {
+            return Err(Error::new_spanned(
+                opt,
+                "first argument of relation attribute must be the target type",
+            ))
+        }
+        None => {
+            return Err(Error::new_spanned(
+                ml,
+                "first argument of relation attribute must be the target type",
+            ))
+        }
     };
+    let sync_getter_name =
+        match nested_iter.next() {
+            Some(NestedMeta::Lit(Lit::Str(s))) => format_ident!("{}", s.value(), span = s.span()),
+            None => format_ident!("{}_sync", ident),
+            Some(opt) => return Err(Error::new_spanned(
+                opt,
+                "second argument of relation attribute must be the synchronous getter name as a \
+                 string literal",
+            )),
+        };
+    let getter_name =
+        match nested_iter.next() {
+            Some(NestedMeta::Lit(Lit::Str(s))) => format_ident!("{}", s.value(), span = s.span()),
+            None => format_ident!("{}", ident),
+            Some(opt) => return Err(Error::new_spanned(
+                opt,
+                "third argument of relation attribute must be the getter name as a string literal",
+            )),
+        };
+    if let Some(opt) = nested_iter.next() {
+        return Err(Error::new_spanned(
+            opt,
+            "relation attribute takes at most a type and two getter names",
+        ));
+    }
 
-    RelationInfo {
+    Ok(RelationInfo {
         model,
         sync_getter_name,
         getter_name,
-    }
+    })
 }
@@ -0,0 +1,146 @@
+use crate::{attr_is_bongo, parse_attr};
+use quote::ToTokens;
+use syn::{Attribute, DeriveInput, Error, FieldsNamed, Meta, NestedMeta, Result};
+
+/// Ways an option can be written.
+#[derive(Clone, Copy, PartialEq)]
+enum Form {
+    /// `name`
+    Word,
+    /// `name(...)`
+    List,
+    /// `name = "..."`
+    Value,
+}
+
+const STRUCT_OPTIONS: &[(&str, &[Form])] = &[
+    ("collection", &[Form::Value]),
+    ("connection", &[Form::Value]),
+    ("hooks", &[Form::Word]),
+    ("index", &[Form::List]),
+    ("referenced_by", &[Form::List]),
+    ("soft_delete", &[Form::Word, Form::List]),
+    ("timestamps", &[Form::Word, Form::List]),
+    ("validate_with", &[Form::Value]),
+    ("validate_with_async", &[Form::Value]),
+];
+
+const FIELD_OPTIONS: &[(&str, &[Form])] = &[
+    ("has_many", &[Form::List]),
+    ("has_one", &[Form::List]),
+    ("index", &[Form::Word, Form::List]),
+    ("ttl", &[Form::Value]),
+    ("unique", &[Form::Word]),
+    ("validate", &[Form::List]),
+    ("validate_with", &[Form::Value]),
+    ("validate_with_async", &[Form::Value]),
+    ("version", &[Form::Word]),
+];
+
+/// Options only used by the `Model` derive, which would silently do nothing on a `BlockingModel`.
+const ASYNC_OPTIONS: &[&str] = &["validate_with_async"];
+
+/// Rejects unknown or malformed options in the `bongo` attributes of the struct and its fields,
+/// reporting every one of them at once. Asynchronous options are rejected unless `asynchronous`
+/// is set.
+pub fn check_options(input: &DeriveInput, fields: &FieldsNamed, asynchronous: bool) -> Result<()> {
+    let mut errors = Vec::new();
+    check_attrs(
+        &input.attrs,
+        STRUCT_OPTIONS,
+        "struct",
+        asynchronous,
+        &mut errors,
+    );
+    for field in &fields.named {
+        check_attrs(
+            &field.attrs,
+            FIELD_OPTIONS,
+            "field",
+            asynchronous,
+            &mut errors,
+        );
+    }
+
+    let mut errors = errors.into_iter();
+    match errors.next() {
+        Some(mut error) => {
+            for e in errors {
+                error.combine(e);
+            }
+            Err(error)
+        }
+        None => Ok(()),
+    }
+}
+
+fn check_attrs(
+    attrs: &[Attribute],
+    options: &[(&str, &[Form])],
+    target: &str,
+    asynchronous: bool,
+    errors: &mut Vec<Error>,
+) {
+    for attr in attrs {
+        if !attr_is_bongo(attr) {
+            continue;
+        }
+
+        let attr = match parse_attr(attr) {
+            Ok(a) => a,
+            Err(e) => {
+                errors.push(e);
+                continue;
+            }
+        };
+        for opt in &attr.nested {
+            let (path, form) = match opt {
+                NestedMeta::Meta(Meta::Path(p)) => (p, Form::Word),
+                NestedMeta::Meta(Meta::List(l)) => (&l.path, Form::List),
+                NestedMeta::Meta(Meta::NameValue(nv)) => (&nv.path, Form::Value),
+                NestedMeta::Lit(l) => {
+                    errors.push(Error::new_spanned(l, "expected a bongo option"));
+                    continue;
+                }
+            };
+
+            let name = path.to_token_stream().to_string();
+            match options.iter().find(|(n, _)| path.is_ident(n)) {
+                Some((n, _)) if !asynchronous && ASYNC_OPTIONS.contains(n) => {
+                    errors.push(Error::new_spanned(
+                        path,
+                        format!("`{}` requires deriving `Model`", name),
+                    ));
+                }
+                Some((_, forms)) if forms.contains(&form) => {}
+                Some((_, forms)) => {
+                    let expected: Vec<_> = forms.iter().map(|f| usage(&name, *f)).collect();
+                    errors.push(Error::new_spanned(
+                        opt,
+                        format!("expected {}", expected.join(" or ")),
+                    ));
+                }
+                None => {
+                    let expected: Vec<_> = options.iter().map(|(n, _)| *n).collect();
+                    errors.push(Error::new_spanned(
+                        path,
+                        format!(
+                            "unknown bongo {} option `{}`, expected one of {}",
+                            target,
+                            name,
+                            expected.join(", "),
+                        ),
+                    ));
+                }
+            }
+        }
+    }
+}
+
+fn usage(name: &str, form: Form) -> String {
+    match form {
+        Form::Word => format!("`{}`", name),
+        Form::List => format!("`{}(...)`", name),
+        Form::Value => format!("`{} = \"...\"`", name),
+    }
+}
@@ -0,0 +1,183 @@
+use crate::{attr_is_bongo, fields::field_constant_ident, parse_attr};
+use quote::{format_ident, quote};
+use syn::{DeriveInput, Error, Ident, Lit, Meta, MetaList, NestedMeta, Path, Result};
+
+/// Getters generated for the inverse relations of a model.
+pub struct InverseRelations {
+    pub getters_sync: Vec<proc_macro2::TokenStream>,
+    pub getters: Vec<proc_macro2::TokenStream>,
+}
+
+/// Generates the getters for every `referenced_by(Model, "field")` struct attribute, loading the
+/// documents of `Model` whose `field` holds the id of this one.
+///
+/// Getters are named after the plural of `Model` in snake case unless given explicitly, following
+/// the regular English rules only, so irregular plurals such as `Person` need explicit names.
+pub fn referenced_by(input: &DeriveInput) -> Result<InverseRelations> {
+    let mut getters_sync = Vec::new();
+    let mut getters = Vec::new();
+
+    for attr in &input.attrs {
+        if !attr_is_bongo(attr) {
+            continue;
+        }
+
+        let attr = parse_attr(attr)?;
+        for opt in attr.nested {
+            let ml = match opt {
+                NestedMeta::Meta(Meta::List(ml)) if ml.path.is_ident("referenced_by") => ml,
+                _ => continue,
+            };
+
+            let InverseRelation {
+                model,
+                key,
+                sync_getter_name,
+                getter_name,
+            } = inverse_relation(&ml)?;
+            let filter = quote! {
+                let mut filter = ::bongo::re_exports::bson::Document::new();
+                filter.insert(#key, ::bongo::BlockingModel::id(self));
+            };
+
+            getters_sync.push(quote! {
+                pub fn #sync_getter_name(&self) -> ::bongo::Result<Vec<#model>> {
+                    use ::bongo::BlockingModel;
+
+                    #filter
+                    #model::find_sync(filter, None, None)
+                }
+            });
+            getters.push(quote! {
+                pub async fn #getter_name(&self) -> ::bongo::Result<Vec<#model>> {
+                    use ::bongo::Model;
+
+                    #filter
+                    #model::find(filter, None, None).await
+                }
+            });
+        }
+    }
+
+    Ok(InverseRelations {
+        getters_sync,
+        getters,
+    })
+}
+
+struct InverseRelation {
+    model: Path,
+    /// Constant of the `Schema::Fields` struct of the model holding the BSON key of the referencing
+    /// field.
+    key: proc_macro2::TokenStream,
+    sync_getter_name: Ident,
+    getter_name: Ident,
+}
+
+fn inverse_relation(ml: &MetaList) -> Result<InverseRelation> {
+    let mut nested = ml.nested.iter();
+
+    let model = match nested.next() {
+        Some(NestedMeta::Meta(Meta::Path(p))) => p.clone(),
+        Some(opt) => {
+            return Err(Error::new_spanned(
+                opt,
+                "first argument of referenced_by must be the referencing type",
+            ))
+        }
+        None => {
+            return Err(Error::new_spanned(
+                ml,
+                "first argument of referenced_by must be the referencing type",
+            ))
+        }
+    };
+    let field = match nested.next() {
+        Some(NestedMeta::Lit(Lit::Str(s))) => s,
+        Some(opt) => return Err(Error::new_spanned(
+            opt,
+            "second argument of referenced_by must be the referencing field as a string literal",
+        )),
+        None => return Err(Error::new_spanned(
+            ml,
+            "second argument of referenced_by must be the referencing field as a string literal",
+        )),
+    };
+
+    let plural = plural(&snake_case(
+        &model.segments.last().unwrap().ident.to_string(),
+    ));
+    let sync_getter_name =
+        match nested.next() {
+            Some(NestedMeta::Lit(Lit::Str(s))) => format_ident!("{}", s.value(), span = s.span()),
+            None => format_ident!("{}_sync", plural),
+            Some(opt) => return Err(Error::new_spanned(
+                opt,
+                "third argument of referenced_by must be the synchronous getter name as a string \
+                 literal",
+            )),
+        };
+    let getter_name = match nested.next() {
+        Some(NestedMeta::Lit(Lit::Str(s))) => format_ident!("{}", s.value(), span = s.span()),
+        None => format_ident!("{}", plural),
+        Some(opt) => {
+            return Err(Error::new_spanned(
+                opt,
+                "fourth argument of referenced_by must be the getter name as a string literal",
+            ))
+        }
+    };
+    if let Some(opt) = nested.next() {
+        return Err(Error::new_spanned(
+            opt,
+            "referenced_by takes at most a type, a field and two getter names",
+        ));
+    }
+
+    if syn::parse_str::<Ident>(&field.value()).is_err() {
+        return Err(Error::new_spanned(
+            field,
+            "second argument of referenced_by must name a field of the referencing type",
+        ));
+    }
+    let constant = field_constant_ident(&field.value(), field.span());
+
+    Ok(InverseRelation {
+        key: quote! { <#model as ::bongo::Schema>::Fields::#constant },
+        model,
+        sync_getter_name,
+        getter_name,
+    })
+}
+
+/// Regular English plural of a snake case name, such as `categories` or `addresses`.
+fn plural(name: &str) -> String {
+    let consonant_y = name.ends_with('y')
+        && !name[..name.len() - 1].ends_with(|c| "aeiou_".contains(c))
+        && name.len() > 1;
+    if consonant_y {
+        format!("{}ies", &name[..name.len() - 1])
+    } else if ["s", "x", "z", "ch", "sh"]
+        .iter()
+        .any(|s| name.ends_with(s))
+    {
+        format!("{}es", name)
+    } else {
+        format!("{}s", name)
+    }
+}
+
+fn snake_case(name: &str) -> String {
+    let mut result = String::with_capacity(name.len() + 4);
+    for (i, c) in name.chars().enumerate() {
+        if c.is_ascii_uppercase() {
+            if i > 0 {
+                result.push('_');
+            }
+            result.push(c.to_ascii_lowercase());
+        } else {
+            result.push(c);
+        }
+    }
+    result
+}
@@ -0,0 +1,65 @@
+use crate::{
+    attr_is_bongo,
+    fields::{field_key, FieldName},
+    parse_attr,
+};
+use quote::quote;
+use syn::{DeriveInput, Error, Lit, Meta, NestedMeta, Result};
+
+/// Generates `soft_delete` for a `soft_delete` or `soft_delete(deleted_at = "...")` struct
+/// attribute, the name being a field or a raw BSON key.
+pub fn soft_delete(
+    input: &DeriveInput,
+    names: &[FieldName],
+) -> Result<Option<proc_macro2::TokenStream>> {
+    let mut result = None;
+    for attr in &input.attrs {
+        if !attr_is_bongo(attr) {
+            continue;
+        }
+
+        let attr = parse_attr(attr)?;
+        for opt in attr.nested {
+            match opt {
+                NestedMeta::Meta(Meta::Path(p)) if p.is_ident("soft_delete") => {
+                    result = Some("deleted_at".to_owned())
+                }
+                NestedMeta::Meta(Meta::List(ml)) if ml.path.is_ident("soft_delete") => {
+                    for opt in &ml.nested {
+                        match opt {
+                            NestedMeta::Meta(Meta::NameValue(nv))
+                                if nv.path.is_ident("deleted_at") =>
+                            {
+                                match &nv.lit {
+                                    Lit::Str(s) => result = Some(s.value()),
+                                    lit => {
+                                        return Err(Error::new_spanned(
+                                            lit,
+                                            "deleted_at should be a string literal",
+                                        ))
+                                    }
+                                }
+                            }
+                            opt => {
+                                return Err(Error::new_spanned(
+                                    opt,
+                                    "soft_delete only accepts deleted_at",
+                                ))
+                            }
+                        }
+                    }
+                }
+                _ => continue,
+            }
+        }
+    }
+
+    Ok(result.map(|deleted_at| {
+        let deleted_at = field_key(names, &deleted_at);
+        quote! {
+            fn soft_delete() -> Option<&'static str> {
+                Some(#deleted_at)
+            }
+        }
+    }))
+}
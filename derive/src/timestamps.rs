@@ -0,0 +1,79 @@
+use crate::{
+    attr_is_bongo,
+    fields::{field_key, FieldName},
+    parse_attr,
+};
+use quote::quote;
+use syn::{DeriveInput, Error, Lit, Meta, NestedMeta, Result};
+
+/// Generates the body of `timestamps` for a `timestamps` or `timestamps(created_at = "...",
+/// updated_at = "...")` struct attribute, the names being fields or raw BSON keys.
+pub fn timestamps(
+    input: &DeriveInput,
+    names: &[FieldName],
+) -> Result<Option<proc_macro2::TokenStream>> {
+    let mut result = None;
+    for attr in &input.attrs {
+        if !attr_is_bongo(attr) {
+            continue;
+        }
+
+        let attr = parse_attr(attr)?;
+        for opt in attr.nested {
+            match opt {
+                NestedMeta::Meta(Meta::Path(p)) if p.is_ident("timestamps") => {
+                    result = Some(("created_at".to_owned(), "updated_at".to_owned()))
+                }
+                NestedMeta::Meta(Meta::List(ml)) if ml.path.is_ident("timestamps") => {
+                    let mut keys = ("created_at".to_owned(), "updated_at".to_owned());
+                    for opt in &ml.nested {
+                        match opt {
+                            NestedMeta::Meta(Meta::NameValue(nv)) => {
+                                let name = match &nv.lit {
+                                    Lit::Str(s) => s.value(),
+                                    lit => {
+                                        return Err(Error::new_spanned(
+                                            lit,
+                                            "timestamp field should be a string literal",
+                                        ))
+                                    }
+                                };
+                                if nv.path.is_ident("created_at") {
+                                    keys.0 = name;
+                                } else if nv.path.is_ident("updated_at") {
+                                    keys.1 = name;
+                                } else {
+                                    return Err(Error::new_spanned(
+                                        &nv.path,
+                                        "timestamps accepts created_at and updated_at",
+                                    ));
+                                }
+                            }
+                            opt => {
+                                return Err(Error::new_spanned(
+                                    opt,
+                                    "timestamps accepts created_at and updated_at",
+                                ))
+                            }
+                        }
+                    }
+                    result = Some(keys);
+                }
+                _ => continue,
+            }
+        }
+    }
+
+    Ok(result.map(|(created_at, updated_at)| {
+        let created_at = field_key(names, &created_at);
+        let updated_at = field_key(names, &updated_at);
+        quote! {
+            fn timestamps() -> Option<::bongo::Timestamps> {
+                Some(::bongo::Timestamps {
+                    created_at: #created_at,
+                    updated_at: #updated_at,
+                })
+            }
+        }
+    }))
+}
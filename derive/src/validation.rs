@@ -0,0 +1,214 @@
+use crate::{attr_is_bongo, fields::FieldName, option_inner, parse_attr};
+use quote::quote;
+use syn::{
+    DeriveInput, Error, Lit, LitFloat, Meta, MetaList, MetaNameValue, NestedMeta, Path, Result,
+    Type,
+};
+
+/// Checks collected by the generated validation methods, `checks` being awaited.
+pub struct Validations {
+    pub checks_sync: Vec<proc_macro2::TokenStream>,
+    pub checks: Vec<proc_macro2::TokenStream>,
+}
+
+/// Generates the checks for the `validate(...)` and `validate_with` attributes of every field,
+/// followed by the `validate_with` attributes of the struct.
+pub fn validations(input: &DeriveInput, names: &[FieldName]) -> Result<Validations> {
+    let mut checks_sync = Vec::new();
+    let mut checks = Vec::new();
+
+    for name in names {
+        let ident = name.field.ident.as_ref().unwrap();
+        let key = &name.key;
+        let (ty, optional) = match option_inner(&name.field.ty) {
+            Some(t) => (t, true),
+            None => (&name.field.ty, false),
+        };
+        let with_value = |checks: proc_macro2::TokenStream| {
+            if optional {
+                quote! {
+                    if let Some(value) = &self.#ident {
+                        #checks
+                    }
+                }
+            } else {
+                quote! {
+                    let value = &self.#ident;
+                    #checks
+                }
+            }
+        };
+
+        for attr in &name.field.attrs {
+            if !attr_is_bongo(attr) {
+                continue;
+            }
+
+            let attr = parse_attr(attr)?;
+            for opt in attr.nested {
+                match opt {
+                    NestedMeta::Meta(Meta::List(ml)) if ml.path.is_ident("validate") => {
+                        let rules = field_checks(&ml, key, ty)?;
+                        checks_sync.push(with_value(quote! { #(#rules)* }));
+                    }
+                    NestedMeta::Meta(Meta::NameValue(nv)) if nv.path.is_ident("validate_with") => {
+                        let path = validator(&nv)?;
+                        checks_sync.push(with_value(quote! {
+                            if let Err(message) = #path(value) {
+                                errors.add(#key, message);
+                            }
+                        }));
+                    }
+                    NestedMeta::Meta(Meta::NameValue(nv))
+                        if nv.path.is_ident("validate_with_async") =>
+                    {
+                        let path = validator(&nv)?;
+                        checks.push(with_value(quote! {
+                            if let Err(message) = #path(value).await {
+                                errors.add(#key, message);
+                            }
+                        }));
+                    }
+                    _ => continue,
+                }
+            }
+        }
+    }
+
+    for attr in &input.attrs {
+        if !attr_is_bongo(attr) {
+            continue;
+        }
+
+        let attr = parse_attr(attr)?;
+        for opt in attr.nested {
+            match opt {
+                NestedMeta::Meta(Meta::NameValue(nv)) if nv.path.is_ident("validate_with") => {
+                    let path = validator(&nv)?;
+                    checks_sync.push(quote! { #path(self, errors); });
+                }
+                NestedMeta::Meta(Meta::NameValue(nv))
+                    if nv.path.is_ident("validate_with_async") =>
+                {
+                    let path = validator(&nv)?;
+                    checks.push(quote! { #path(self, errors).await; });
+                }
+                _ => continue,
+            }
+        }
+    }
+
+    Ok(Validations {
+        checks_sync,
+        checks,
+    })
+}
+
+fn validator(nv: &MetaNameValue) -> Result<Path> {
+    match &nv.lit {
+        Lit::Str(s) => s
+            .parse()
+            .map_err(|_| Error::new_spanned(s, "validator should be a function path")),
+        lit => Err(Error::new_spanned(
+            lit,
+            "validator should be a function path as a string literal",
+        )),
+    }
+}
+
+fn field_checks(ml: &MetaList, key: &str, ty: &Type) -> Result<Vec<proc_macro2::TokenStream>> {
+    let mut checks = Vec::new();
+    for opt in &ml.nested {
+        let check = match opt {
+            NestedMeta::Meta(Meta::NameValue(nv)) if nv.path.is_ident("min_len") => {
+                let min = length(&nv.lit)?;
+                quote! { ::bongo::check_min_len(errors, #key, value, #min); }
+            }
+            NestedMeta::Meta(Meta::NameValue(nv)) if nv.path.is_ident("max_len") => {
+                let max = length(&nv.lit)?;
+                quote! { ::bongo::check_max_len(errors, #key, value, #max); }
+            }
+            NestedMeta::Meta(Meta::NameValue(nv)) if nv.path.is_ident("regex") => {
+                let pattern = match &nv.lit {
+                    Lit::Str(s) => s.value(),
+                    lit => return Err(Error::new_spanned(lit, "regex should be a string literal")),
+                };
+                if let Err(e) = regex::Regex::new(&pattern) {
+                    return Err(Error::new_spanned(&nv.lit, format!("invalid regex: {}", e)));
+                }
+                quote! {
+                    {
+                        use ::bongo::re_exports::{once_cell::sync::Lazy, regex::Regex};
+
+                        static REGEX: Lazy<Regex> = Lazy::new(|| Regex::new(#pattern).unwrap());
+                        ::bongo::check_regex(errors, #key, value.as_ref(), &REGEX);
+                    }
+                }
+            }
+            NestedMeta::Meta(Meta::Path(p)) if p.is_ident("email") => {
+                quote! { ::bongo::check_email(errors, #key, value.as_ref()); }
+            }
+            NestedMeta::Meta(Meta::List(l)) if l.path.is_ident("range") => {
+                let mut min = quote! { None };
+                let mut max = quote! { None };
+                for opt in &l.nested {
+                    match opt {
+                        NestedMeta::Meta(Meta::NameValue(nv)) if nv.path.is_ident("min") => {
+                            let lit = bound(&nv.lit, ty)?;
+                            min = quote! { Some(#lit) };
+                        }
+                        NestedMeta::Meta(Meta::NameValue(nv)) if nv.path.is_ident("max") => {
+                            let lit = bound(&nv.lit, ty)?;
+                            max = quote! { Some(#lit) };
+                        }
+                        opt => {
+                            return Err(Error::new_spanned(
+                                opt,
+                                "range accepts min and max numeric bounds",
+                            ))
+                        }
+                    }
+                }
+                quote! { ::bongo::check_range(errors, #key, value, #min, #max); }
+            }
+            opt => {
+                return Err(Error::new_spanned(
+                    opt,
+                    "invalid validation rule, expected one of min_len, max_len, regex, email or \
+                     range",
+                ))
+            }
+        };
+        checks.push(check);
+    }
+    Ok(checks)
+}
+
+fn length(lit: &Lit) -> Result<usize> {
+    match lit {
+        Lit::Int(i) => i.base10_parse(),
+        _ => Err(Error::new_spanned(
+            lit,
+            "length should be an integer literal",
+        )),
+    }
+}
+
+/// Turns integer bounds into float literals on float fields, so `range(min = 0)` works on `f64`.
+fn bound(lit: &Lit, ty: &Type) -> Result<Lit> {
+    let float = match ty {
+        Type::Path(p) => p.path.is_ident("f32") || p.path.is_ident("f64"),
+        _ => false,
+    };
+    match lit {
+        Lit::Int(i) if float => Ok(Lit::Float(LitFloat::new(
+            &format!("{}.0", i.base10_digits()),
+            i.span(),
+        ))),
+        Lit::Int(_) | Lit::Float(_) => Ok(lit.clone()),
+        _ => Err(Error::new_spanned(
+            lit,
+            "range bounds should be numeric literals",
+        )),
+    }
+}
@@ -0,0 +1,60 @@
+use crate::{attr_is_bongo, fields::FieldName, parse_attr};
+use quote::quote;
+use syn::{Error, Meta, NestedMeta, Result, Type};
+
+/// Generates `version` and `set_version` for the integer field annotated with `version`.
+pub fn version(names: &[FieldName]) -> Result<Option<proc_macro2::TokenStream>> {
+    let mut result = None;
+    for name in names {
+        for attr in &name.field.attrs {
+            if !attr_is_bongo(attr) {
+                continue;
+            }
+
+            let attr = parse_attr(attr)?;
+            for opt in attr.nested {
+                match opt {
+                    NestedMeta::Meta(Meta::Path(p)) if p.is_ident("version") => {
+                        if result.is_some() {
+                            return Err(Error::new_spanned(p, "only one field can be the version"));
+                        }
+                        if !is_integer(&name.field.ty) {
+                            return Err(Error::new_spanned(
+                                &name.field.ty,
+                                "the version must be an integer",
+                            ));
+                        }
+                        result = Some(name);
+                    }
+                    _ => continue,
+                }
+            }
+        }
+    }
+
+    Ok(result.map(|name| {
+        let ident = name.field.ident.as_ref().unwrap();
+        let key = &name.key;
+        quote! {
+            fn version(&self) -> Option<(&'static str, i64)> {
+                Some((#key, self.#ident as i64))
+            }
+            fn set_version(&mut self, version: i64) {
+                self.#ident = version as _;
+            }
+        }
+    }))
+}
+
+const INTEGERS: &[&str] = &[
+    "i8", "i16", "i32", "i64", "isize", "u8", "u16", "u32", "u64", "usize",
+];
+
+fn is_integer(ty: &Type) -> bool {
+    match ty {
+        Type::Path(p) => INTEGERS
+            .iter()
+            .any(|i| p.qself.is_none() && p.path.is_ident(i)),
+        _ => false,
+    }
+}
@@ -0,0 +1,102 @@
+use crate::{BlockingModel, Result};
+use mongodb::Cursor;
+use std::marker::PhantomData;
+
+/// Iterator over the results of a query, decoding each document as it is pulled from the server.
+pub struct ModelCursor<M> {
+    cursor: Cursor,
+    model: PhantomData<fn() -> M>,
+}
+
+impl<M> ModelCursor<M> {
+    pub(crate) fn new(cursor: Cursor) -> Self {
+        Self {
+            cursor,
+            model: PhantomData,
+        }
+    }
+}
+
+impl<M: BlockingModel> Iterator for ModelCursor<M> {
+    type Item = Result<M>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.cursor.next().map(|r| match r {
+            Ok(d) => crate::from_document(d),
+            Err(e) => Err(e.into()),
+        })
+    }
+}
+
+#[cfg(feature = "async")]
+pub use self::stream::ModelStream;
+
+#[cfg(feature = "async")]
+mod stream {
+    use super::ModelCursor;
+    use crate::{Model, Result};
+    use futures::{
+        channel::mpsc::{self, Receiver},
+        executor,
+        future::BoxFuture,
+        ready, FutureExt, SinkExt, Stream,
+    };
+    use std::{
+        pin::Pin,
+        task::{Context, Poll},
+    };
+
+    /// Number of decoded documents buffered ahead of the consumer.
+    const BUFFER: usize = 16;
+
+    /// Stream over the results of a query.
+    ///
+    /// Documents are pulled from the server and decoded on a blocking thread which pauses as soon
+    /// as the buffer is full, and stops as soon as the stream is dropped. The async `after_load`
+    /// hook runs on each document as it is polled.
+    pub struct ModelStream<M> {
+        receiver: Receiver<Result<M>>,
+        loading: Option<BoxFuture<'static, Result<M>>>,
+    }
+
+    impl<M: Model> ModelStream<M> {
+        pub(crate) fn new(cursor: ModelCursor<M>) -> Self {
+            let (mut sender, receiver) = mpsc::channel(BUFFER);
+            tokio::task::spawn_blocking(move || {
+                for item in cursor {
+                    if executor::block_on(sender.send(item)).is_err() {
+                        break;
+                    }
+                }
+            });
+            Self {
+                receiver,
+                loading: None,
+            }
+        }
+    }
+
+    impl<M: Model> Stream for ModelStream<M> {
+        type Item = Result<M>;
+
+        fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+            loop {
+                if let Some(loading) = &mut self.loading {
+                    let result = ready!(loading.poll_unpin(cx));
+                    self.loading = None;
+                    return Poll::Ready(Some(result));
+                }
+
+                match ready!(Pin::new(&mut self.receiver).poll_next(cx)) {
+                    Some(Ok(mut doc)) => {
+                        self.loading = Some(Box::pin(async move {
+                            doc.after_load().await?;
+                            Ok(doc)
+                        }))
+                    }
+                    item => return Poll::Ready(item),
+                }
+            }
+        }
+    }
+}
@@ -8,12 +8,20 @@ pub enum Error {
     BsonDecode(#[from] bson::DecoderError),
     #[error("bson decoding error: {0}")]
     BsonEncode(#[from] bson::EncoderError),
-    #[error("tried to connect multiple times")]
-    AlreadyConnected,
-    #[error("tried to access unconnected client")]
-    NotConnected,
+    #[error("tried to connect \"{0}\" multiple times")]
+    AlreadyConnected(String),
+    #[error("tried to access unconnected client \"{0}\"")]
+    NotConnected(String),
     #[error("relational error: {0}")]
     Relation(String),
+    #[error("validation error: {0}")]
+    Validation(crate::ValidationErrors),
+    #[error("aborted by hook: {0}")]
+    Aborted(String),
+    #[error("version conflict: {0} was saved by someone else since it was loaded")]
+    VersionConflict(bson::Document),
+    #[error("{0} is versioned and must be saved through save_versioned")]
+    Versioned(bson::Document),
 
     #[cfg(feature = "tokio")]
     #[cfg_attr(feature = "tokio", error("task error: {0}"))]
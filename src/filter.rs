@@ -0,0 +1,199 @@
+use crate::Result;
+use bson::{doc, Bson, Document, EncoderResult};
+use serde::Serialize;
+use std::{marker::PhantomData, ops::Not};
+
+/// Filter on the documents of a model, built from the `filter` function generated by the derives.
+///
+/// Taken by the filtering methods of `BlockingModel` and `Model`. A value which can't be
+/// represented in BSON (like a `u64` over `i64::MAX`) makes the filter fail with
+/// `Error::BsonEncode` when the operation runs.
+pub struct Filter<M> {
+    document: EncoderResult<Document>,
+    model: PhantomData<fn() -> M>,
+}
+
+impl<M> Filter<M> {
+    fn new(document: EncoderResult<Document>) -> Self {
+        Self {
+            document,
+            model: PhantomData,
+        }
+    }
+
+    pub fn and(self, other: Filter<M>) -> Self {
+        Self::new(combine("$and", self.document, other.document))
+    }
+    pub fn or(self, other: Filter<M>) -> Self {
+        Self::new(combine("$or", self.document, other.document))
+    }
+    pub fn into_document(self) -> Result<Document> {
+        Ok(self.document?)
+    }
+}
+
+impl<M> Not for Filter<M> {
+    type Output = Self;
+
+    fn not(self) -> Self {
+        Self::new(self.document.map(|d| doc! {"$nor": [d]}))
+    }
+}
+
+/// Filters on the documents of the model `M` taken by the methods where a filter is optional,
+/// like finds and counts.
+///
+/// Raw documents are accepted for any model, typed filters only for their own.
+pub trait IntoFilter<M> {
+    fn into_filter(self) -> Result<Option<Document>>;
+}
+
+impl<M> IntoFilter<M> for Option<Document> {
+    fn into_filter(self) -> Result<Option<Document>> {
+        Ok(self)
+    }
+}
+impl<M> IntoFilter<M> for Document {
+    fn into_filter(self) -> Result<Option<Document>> {
+        Ok(Some(self))
+    }
+}
+impl<M> IntoFilter<M> for Filter<M> {
+    fn into_filter(self) -> Result<Option<Document>> {
+        self.into_document().map(Some)
+    }
+}
+
+/// Filters on the documents of the model `M` taken by the methods where a filter is required,
+/// like updates and deletes.
+///
+/// Raw documents are accepted for any model, typed filters only for their own.
+pub trait IntoQuery<M> {
+    fn into_query(self) -> Result<Document>;
+}
+
+impl<M> IntoQuery<M> for Document {
+    fn into_query(self) -> Result<Document> {
+        Ok(self)
+    }
+}
+impl<M> IntoQuery<M> for Filter<M> {
+    fn into_query(self) -> Result<Document> {
+        self.into_document()
+    }
+}
+
+/// Flattens chains of the same logical operator into a single array.
+fn combine(
+    operator: &str,
+    left: EncoderResult<Document>,
+    right: EncoderResult<Document>,
+) -> EncoderResult<Document> {
+    let (left, right) = (left?, right?);
+    let mut operands = Vec::new();
+    for d in [left, right] {
+        match d.get_array(operator) {
+            Ok(a) if d.len() == 1 => operands.extend(a.iter().cloned()),
+            _ => operands.push(Bson::Document(d)),
+        }
+    }
+    Ok(doc! {operator: operands})
+}
+
+/// Conditions on a single field of type `T` of the model `M`.
+pub struct FieldFilter<M, T> {
+    key: &'static str,
+    model: PhantomData<fn() -> (M, T)>,
+}
+
+impl<M, T: Serialize> FieldFilter<M, T> {
+    #[doc(hidden)]
+    pub fn new(key: &'static str) -> Self {
+        Self {
+            key,
+            model: PhantomData,
+        }
+    }
+
+    fn op(self, operator: &str, value: EncoderResult<Bson>) -> Filter<M> {
+        Filter::new(value.map(|v| doc! {self.key: {operator: v}}))
+    }
+
+    pub fn eq<V: Into<T>>(self, value: V) -> Filter<M> {
+        Filter::new(value_to_bson(&value.into()).map(|v| doc! {self.key: v}))
+    }
+    pub fn ne<V: Into<T>>(self, value: V) -> Filter<M> {
+        self.op("$ne", value_to_bson(&value.into()))
+    }
+    pub fn gt<V: Into<T>>(self, value: V) -> Filter<M> {
+        self.op("$gt", value_to_bson(&value.into()))
+    }
+    pub fn gte<V: Into<T>>(self, value: V) -> Filter<M> {
+        self.op("$gte", value_to_bson(&value.into()))
+    }
+    pub fn lt<V: Into<T>>(self, value: V) -> Filter<M> {
+        self.op("$lt", value_to_bson(&value.into()))
+    }
+    pub fn lte<V: Into<T>>(self, value: V) -> Filter<M> {
+        self.op("$lte", value_to_bson(&value.into()))
+    }
+    pub fn is_in<I, V>(self, values: I) -> Filter<M>
+    where
+        I: IntoIterator<Item = V>,
+        V: Into<T>,
+    {
+        self.op("$in", values_to_bson(values).map(Bson::Array))
+    }
+    pub fn not_in<I, V>(self, values: I) -> Filter<M>
+    where
+        I: IntoIterator<Item = V>,
+        V: Into<T>,
+    {
+        self.op("$nin", values_to_bson(values).map(Bson::Array))
+    }
+    pub fn exists(self, exists: bool) -> Filter<M> {
+        self.op("$exists", Ok(Bson::Boolean(exists)))
+    }
+}
+
+impl<M> FieldFilter<M, String> {
+    pub fn regex(self, pattern: &str) -> Filter<M> {
+        self.op("$regex", Ok(Bson::String(pattern.to_owned())))
+    }
+}
+
+impl<M, T: Serialize> FieldFilter<M, Vec<T>> {
+    /// Matches arrays containing the given element.
+    pub fn contains<V: Into<T>>(self, value: V) -> Filter<M> {
+        Filter::new(value_to_bson(&value.into()).map(|v| doc! {self.key: v}))
+    }
+    /// Matches arrays containing every given element.
+    pub fn contains_all<I, V>(self, values: I) -> Filter<M>
+    where
+        I: IntoIterator<Item = V>,
+        V: Into<T>,
+    {
+        self.op("$all", values_to_bson(values).map(Bson::Array))
+    }
+    pub fn size(self, size: i64) -> Filter<M> {
+        self.op("$size", Ok(Bson::I64(size)))
+    }
+}
+
+/// Encodes a field value, which fails if it can't be represented in BSON (like a `u64` over
+/// `i64::MAX`).
+pub(crate) fn value_to_bson<T: Serialize>(value: &T) -> EncoderResult<Bson> {
+    bson::to_bson(value)
+}
+
+pub(crate) fn values_to_bson<I, V, T>(values: I) -> EncoderResult<Vec<Bson>>
+where
+    I: IntoIterator<Item = V>,
+    V: Into<T>,
+    T: Serialize,
+{
+    values
+        .into_iter()
+        .map(|v| value_to_bson(&v.into()))
+        .collect()
+}
@@ -1,34 +1,129 @@
 use crate::{Error, Result};
-use mongodb::{options::ClientOptions, Client, Database};
-use once_cell::sync::OnceCell;
+use mongodb::{options::ClientOptions, Client, Collection, Database};
+use once_cell::sync::Lazy;
+use std::{
+    collections::HashMap,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        RwLock,
+    },
+};
 
-static CLIENT: OnceCell<Client> = OnceCell::new();
-static DATABASE: OnceCell<Database> = OnceCell::new();
+/// Name of the connection used by `connect` and by models without a `connection` attribute.
+pub const DEFAULT_CONNECTION: &str = "default";
 
-pub fn client() -> Result<&'static Client> {
-    match CLIENT.get() {
-        Some(c) => Ok(c),
-        None => Err(Error::NotConnected),
+struct Connection {
+    client: Client,
+    database: Database,
+}
+
+static CONNECTIONS: Lazy<RwLock<HashMap<String, Connection>>> = Lazy::new(Default::default);
+/// Bumped every time a connection is removed or replaced, invalidating every `CollectionCache`.
+static GENERATION: AtomicUsize = AtomicUsize::new(0);
+
+pub fn client() -> Result<Client> {
+    client_named(DEFAULT_CONNECTION)
+}
+pub fn database() -> Result<Database> {
+    database_named(DEFAULT_CONNECTION)
+}
+
+pub fn client_named(name: &str) -> Result<Client> {
+    match CONNECTIONS.read().unwrap().get(name) {
+        Some(c) => Ok(c.client.clone()),
+        None => Err(Error::NotConnected(name.to_owned())),
     }
 }
-pub fn database() -> Result<&'static Database> {
-    match DATABASE.get() {
-        Some(d) => Ok(d),
-        None => Err(Error::NotConnected),
+pub fn database_named(name: &str) -> Result<Database> {
+    match CONNECTIONS.read().unwrap().get(name) {
+        Some(c) => Ok(c.database.clone()),
+        None => Err(Error::NotConnected(name.to_owned())),
     }
 }
 
 pub fn connect(uri: &str, database: &str) -> Result<()> {
-    connect_with_options(ClientOptions::parse(uri)?, database)
+    connect_named(DEFAULT_CONNECTION, uri, database)
 }
 pub fn connect_with_options(options: ClientOptions, database: &str) -> Result<()> {
+    connect_named_with_options(DEFAULT_CONNECTION, options, database)
+}
+
+pub fn connect_named(name: &str, uri: &str, database: &str) -> Result<()> {
+    connect_named_with_options(name, ClientOptions::parse(uri)?, database)
+}
+pub fn connect_named_with_options(
+    name: &str,
+    options: ClientOptions,
+    database: &str,
+) -> Result<()> {
+    let mut connections = CONNECTIONS.write().unwrap();
+    if connections.contains_key(name) {
+        return Err(Error::AlreadyConnected(name.to_owned()));
+    }
+
     let client = Client::with_options(options)?;
-    if CLIENT.set(client).is_err() {
-        return Err(Error::AlreadyConnected);
+    let database = client.database(database);
+    connections.insert(name.to_owned(), Connection { client, database });
+
+    Ok(())
+}
+
+pub fn disconnect() -> Result<()> {
+    disconnect_named(DEFAULT_CONNECTION)
+}
+pub fn reconnect(options: ClientOptions) -> Result<()> {
+    reconnect_named(DEFAULT_CONNECTION, options)
+}
+
+pub fn disconnect_named(name: &str) -> Result<()> {
+    let mut connections = CONNECTIONS.write().unwrap();
+    if connections.remove(name).is_none() {
+        return Err(Error::NotConnected(name.to_owned()));
     }
+    GENERATION.fetch_add(1, Ordering::AcqRel);
+
+    Ok(())
+}
+/// Replaces the client of an existing connection, keeping the same database name.
+pub fn reconnect_named(name: &str, options: ClientOptions) -> Result<()> {
+    let mut connections = CONNECTIONS.write().unwrap();
+    let connection = match connections.get_mut(name) {
+        Some(c) => c,
+        None => return Err(Error::NotConnected(name.to_owned())),
+    };
 
-    let database = CLIENT.get().unwrap().database(database);
-    DATABASE.set(database).unwrap();
+    let client = Client::with_options(options)?;
+    connection.database = client.database(connection.database.name());
+    connection.client = client;
+    GENERATION.fetch_add(1, Ordering::AcqRel);
 
     Ok(())
 }
+
+/// Per-model collection handle, refreshed whenever the connections change.
+#[doc(hidden)]
+pub struct CollectionCache {
+    cached: RwLock<Option<(usize, Collection)>>,
+}
+
+impl CollectionCache {
+    #[allow(clippy::new_without_default)]
+    pub const fn new() -> Self {
+        Self {
+            cached: RwLock::new(None),
+        }
+    }
+
+    pub fn get(&self, connection: &str, name: &str) -> Result<Collection> {
+        let generation = GENERATION.load(Ordering::Acquire);
+        if let Some((g, c)) = &*self.cached.read().unwrap() {
+            if *g == generation {
+                return Ok(c.clone());
+            }
+        }
+
+        let collection = database_named(connection)?.collection(name);
+        *self.cached.write().unwrap() = Some((generation, collection.clone()));
+        Ok(collection)
+    }
+}
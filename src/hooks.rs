@@ -0,0 +1,51 @@
+use crate::Result;
+
+/// Lifecycle callbacks of a model, left empty by the derives unless the model is annotated with
+/// `#[bongo(hooks)]`, in which case they are implemented by hand.
+///
+/// Returning an error, typically `Error::Aborted`, from a `before_` hook aborts the operation, and
+/// returning one from `after_load_sync` fails the find which loaded the document. These also run
+/// during async operations, before the matching `Hooks` method.
+pub trait BlockingHooks {
+    fn before_save_sync(&self) -> Result<()> {
+        Ok(())
+    }
+    fn after_save_sync(&self) -> Result<()> {
+        Ok(())
+    }
+    fn before_remove_sync(&self) -> Result<()> {
+        Ok(())
+    }
+    fn after_remove_sync(&self) -> Result<()> {
+        Ok(())
+    }
+    /// Called on every document returned by the find family, cursors included.
+    fn after_load_sync(&mut self) -> Result<()> {
+        Ok(())
+    }
+}
+
+#[cfg(feature = "async")]
+use async_trait::async_trait;
+
+/// Asynchronous lifecycle callbacks of a model, only called by the async operations.
+#[cfg(feature = "async")]
+#[async_trait]
+pub trait Hooks: BlockingHooks + Send + Sync {
+    async fn before_save(&self) -> Result<()> {
+        Ok(())
+    }
+    async fn after_save(&self) -> Result<()> {
+        Ok(())
+    }
+    async fn before_remove(&self) -> Result<()> {
+        Ok(())
+    }
+    async fn after_remove(&self) -> Result<()> {
+        Ok(())
+    }
+    /// Called on every document returned by the find family, streams included.
+    async fn after_load(&mut self) -> Result<()> {
+        Ok(())
+    }
+}
@@ -0,0 +1,240 @@
+use crate::Result;
+use bson::{doc, Bson, Document};
+use mongodb::{error::ErrorKind, options::IndexModel, Collection, Database};
+
+/// Server error code returned when listing the indexes of a collection which doesn't exist yet.
+const NAMESPACE_NOT_FOUND: i32 = 26;
+
+/// Outcome of an index synchronization, listing index names.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct IndexSyncReport {
+    pub created: Vec<String>,
+    /// Indexes dropped and created again because their keys or options changed.
+    pub rebuilt: Vec<String>,
+    pub kept: Vec<String>,
+    pub dropped: Vec<String>,
+}
+
+/// Builds the model of an index declared through the derives.
+#[doc(hidden)]
+pub fn index_model(keys: Document, options: Document) -> IndexModel {
+    IndexModel {
+        keys,
+        options: Some(options),
+    }
+}
+
+fn index_name(index: &IndexModel) -> String {
+    match index.options.as_ref().and_then(|o| o.get_str("name").ok()) {
+        Some(n) => n.to_owned(),
+        None => index
+            .keys
+            .iter()
+            .map(|(k, v)| match v {
+                Bson::String(s) => format!("{}_{}", k, s),
+                v => format!("{}_{}", k, v),
+            })
+            .collect::<Vec<_>>()
+            .join("_"),
+    }
+}
+
+fn existing_indexes(database: &Database, collection: &str) -> Result<Vec<Document>> {
+    let result = match database.run_command(doc! {"listIndexes": collection}, None) {
+        Ok(r) => r,
+        Err(e) => {
+            return match e.kind.as_ref() {
+                ErrorKind::CommandError(c) if c.code == NAMESPACE_NOT_FOUND => Ok(Vec::new()),
+                _ => Err(e.into()),
+            }
+        }
+    };
+
+    let batch = result
+        .get_document("cursor")
+        .and_then(|c| c.get_array("firstBatch"))
+        .map(|b| b.as_slice())
+        .unwrap_or_default();
+    Ok(batch
+        .iter()
+        .filter_map(|i| match i {
+            Bson::Document(d) => Some(d.clone()),
+            _ => None,
+        })
+        .collect())
+}
+
+/// Index options compared with the existing indexes, any difference requiring a rebuild.
+const COMPARED_OPTIONS: &[&str] = &[
+    "unique",
+    "sparse",
+    "expireAfterSeconds",
+    "partialFilterExpression",
+    "collation",
+];
+
+/// Whether an existing index no longer matches the declared specification with the same name,
+/// looking at its keys and at the options in `COMPARED_OPTIONS`.
+fn index_differs(declared: &Document, existing: &Document) -> bool {
+    if !same_value(
+        &Bson::Document(declared.get_document("key").cloned().unwrap_or_default()),
+        &Bson::Document(existing.get_document("key").cloned().unwrap_or_default()),
+    ) {
+        return true;
+    }
+
+    COMPARED_OPTIONS.iter().any(|option| {
+        match (declared.get(option), existing.get(option)) {
+            (None, None) => false,
+            // Flags set to false are the same as missing ones.
+            (Some(Bson::Boolean(false)), None) | (None, Some(Bson::Boolean(false))) => false,
+            // The server fills in every collation field, so only the declared ones are compared.
+            (Some(Bson::Document(d)), Some(Bson::Document(e))) if *option == "collation" => d
+                .iter()
+                .any(|(k, v)| !matches!(e.get(k), Some(e) if same_value(v, e))),
+            (Some(d), Some(e)) => !same_value(d, e),
+            _ => true,
+        }
+    })
+}
+
+/// Compares BSON values, numbers by value whatever their type since the server may not store
+/// them with the declared one.
+fn same_value(a: &Bson, b: &Bson) -> bool {
+    match (a, b) {
+        (Bson::Document(a), Bson::Document(b)) => {
+            a.len() == b.len()
+                && a.iter()
+                    .zip(b.iter())
+                    .all(|((ka, va), (kb, vb))| ka == kb && same_value(va, vb))
+        }
+        (Bson::Array(a), Bson::Array(b)) => {
+            a.len() == b.len() && a.iter().zip(b).all(|(a, b)| same_value(a, b))
+        }
+        (a, b) => match (number(a), number(b)) {
+            (Some(a), Some(b)) => (a - b).abs() < f64::EPSILON,
+            _ => a == b,
+        },
+    }
+}
+
+fn number(value: &Bson) -> Option<f64> {
+    match value {
+        Bson::I32(i) => Some(f64::from(*i)),
+        Bson::I64(i) => Some(*i as f64),
+        Bson::FloatingPoint(f) => Some(*f),
+        _ => None,
+    }
+}
+
+/// Creates the declared indexes missing from the collection, rebuilds the ones whose keys or
+/// options changed, and drops the undeclared ones if `drop_undeclared` is set. The default `_id_`
+/// index is always kept.
+pub(crate) fn sync_indexes(
+    database: &Database,
+    collection: &Collection,
+    indexes: Vec<IndexModel>,
+    drop_undeclared: bool,
+) -> Result<IndexSyncReport> {
+    let name = collection.name();
+    let existing = existing_indexes(database, name)?;
+    let mut report = IndexSyncReport::default();
+
+    let mut missing = Vec::new();
+    let mut declared = Vec::with_capacity(indexes.len());
+    for index in indexes {
+        let index_name = index_name(&index);
+        let mut spec = index.options.unwrap_or_default();
+        spec.insert("key", index.keys);
+        spec.insert("name", index_name.clone());
+
+        match existing
+            .iter()
+            .find(|e| e.get_str("name") == Ok(index_name.as_str()))
+        {
+            Some(e) if !index_differs(&spec, e) => report.kept.push(index_name.clone()),
+            Some(_) => {
+                database.run_command(doc! {"dropIndexes": name, "index": &index_name}, None)?;
+                missing.push(Bson::Document(spec));
+                report.rebuilt.push(index_name.clone());
+            }
+            None => {
+                missing.push(Bson::Document(spec));
+                report.created.push(index_name.clone());
+            }
+        }
+        declared.push(index_name);
+    }
+
+    if !missing.is_empty() {
+        database.run_command(doc! {"createIndexes": name, "indexes": missing}, None)?;
+    }
+
+    for index_name in existing.iter().filter_map(|e| e.get_str("name").ok()) {
+        if index_name == "_id_" || declared.iter().any(|d| d == index_name) {
+            continue;
+        }
+        if drop_undeclared {
+            database.run_command(doc! {"dropIndexes": name, "index": index_name}, None)?;
+            report.dropped.push(index_name.to_owned());
+        } else {
+            report.kept.push(index_name.to_owned());
+        }
+    }
+
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn existing() -> Document {
+        doc! {
+            "v": 2,
+            "key": {"user": 1, "created_at": -1},
+            "name": "by_user",
+            "unique": true,
+            "expireAfterSeconds": 3600,
+            "collation": {"locale": "fr", "strength": 2, "caseLevel": false, "version": "57.1"},
+        }
+    }
+
+    fn declared() -> Document {
+        doc! {
+            "key": {"user": 1, "created_at": -1},
+            "name": "by_user",
+            "unique": true,
+            "sparse": false,
+            "expireAfterSeconds": 3600i64,
+            "collation": {"locale": "fr", "strength": 2},
+        }
+    }
+
+    #[test]
+    fn matches_equivalent_indexes() {
+        assert!(!index_differs(&declared(), &existing()));
+    }
+
+    #[test]
+    fn detects_changed_keys_and_options() {
+        let changes = vec![
+            doc! {"key": {"user": 1, "created_at": 1}},
+            doc! {"key": {"created_at": -1, "user": 1}},
+            doc! {"unique": false},
+            doc! {"sparse": true},
+            doc! {"expireAfterSeconds": 60i64},
+            doc! {"partialFilterExpression": {"active": true}},
+            doc! {"collation": {"locale": "en"}},
+        ];
+        for change in changes {
+            let mut declared = declared();
+            declared.extend(change.clone());
+            assert!(index_differs(&declared, &existing()), "{}", change);
+        }
+
+        let mut declared = declared();
+        declared.remove("collation");
+        assert!(index_differs(&declared, &existing()));
+    }
+}
@@ -1,113 +1,416 @@
+mod cursor;
 mod error;
+mod filter;
 mod globals;
+mod hooks;
+mod index;
+mod query;
 #[doc(hidden)]
 pub mod re_exports;
+mod registry;
+mod relation;
+mod scope;
+mod timestamps;
+
+#[cfg(feature = "async")]
+#[doc(hidden)]
+pub use crate::relation::{check_related, find_related};
+#[doc(hidden)]
+pub use crate::{
+    index::index_model,
+    relation::{check_related_sync, find_related_sync},
+    validation::{check_email, check_max_len, check_min_len, check_range, check_regex},
+};
+mod update;
+mod validation;
 
 #[cfg(feature = "derive")]
 pub use bongo_derive::BlockingModel;
 #[cfg(all(feature = "derive", feature = "async"))]
 pub use bongo_derive::Model;
 
-pub use crate::{error::Error, globals::*};
-use bson::{bson, doc, Bson, Document};
+use crate::scope::Scope;
+pub use crate::{
+    cursor::ModelCursor,
+    error::Error,
+    filter::{FieldFilter, Filter, IntoFilter, IntoQuery},
+    globals::*,
+    hooks::BlockingHooks,
+    index::IndexSyncReport,
+    query::Query,
+    registry::{registered_models, sync_all_indexes, ModelInfo},
+    relation::Relation,
+    timestamps::Timestamps,
+    update::{FieldUpdate, IntoUpdate, Numeric, Update},
+    validation::{Length, ValidationError, ValidationErrors},
+};
+#[cfg(feature = "async")]
+pub use crate::{cursor::ModelStream, hooks::Hooks};
+use bson::{doc, Bson, Document};
 use mongodb::{
-    options::{ReplaceOptions, UpdateModifications},
+    error::{ErrorKind, WriteFailure},
+    options::{
+        CountOptions, FindOneAndDeleteOptions, FindOneAndReplaceOptions, FindOneAndUpdateOptions,
+        FindOneOptions, FindOptions, IndexModel, ReplaceOptions, UpdateModifications,
+        UpdateOptions,
+    },
     results::*,
-    Collection,
+    Collection, Database,
 };
 use serde::{de::DeserializeOwned, Serialize};
 
 pub type Result<T> = std::result::Result<T, Error>;
 
-pub trait BlockingModel: DeserializeOwned + Serialize {
+/// Typed builders generated by the derives for a model.
+///
+/// The builder types are only reachable through this trait, so they never clash with the items
+/// of the module declaring the model, and methods of the model with the same names take
+/// precedence over the ones of this trait.
+pub trait Schema {
+    /// Struct holding the BSON key of every field as a constant.
+    type Fields;
+    /// Typed filter builder, with a method per field.
+    type Filter;
+    /// Typed update builder, with a method per field but the id.
+    type Update;
+    /// Relations of the model, to populate in bulk.
+    type Relations;
+
+    fn filter() -> Self::Filter;
+    fn update() -> Self::Update;
+    fn relations() -> Self::Relations;
+}
+
+pub trait BlockingModel: BlockingHooks + DeserializeOwned + Serialize {
     #[cfg(not(feature = "async"))]
     type Id: Into<Bson> + Clone;
     #[cfg(feature = "async")]
     type Id: Into<Bson> + Clone + Send;
 
-    fn collection() -> Result<&'static Collection>;
+    fn database() -> Result<Database>;
+    fn collection() -> Result<Collection>;
 
     fn id(&self) -> Self::Id;
     fn id_query(&self) -> Document {
         doc! {"_id": self.id().into()}
     }
 
+    fn timestamps() -> Option<Timestamps> {
+        None
+    }
+    /// BSON key and current value of the `#[bongo(version)]` field, if any.
+    fn version(&self) -> Option<(&'static str, i64)> {
+        None
+    }
+    fn set_version(&mut self, _version: i64) {}
+    /// BSON key of the deletion timestamp of models annotated with `#[bongo(soft_delete)]`.
+    ///
+    /// Soft deleted models are removed by setting this timestamp, and reads, counts, updates,
+    /// replacements and deletes skip documents where it is set unless their filter mentions it.
+    /// Deleting never stamps a document again, and saving a model writes it whatever its
+    /// timestamp.
+    fn soft_delete() -> Option<&'static str> {
+        None
+    }
+
+    /// Runs the declared validation rules and synchronous custom validators, adding every failure
+    /// to `errors`.
+    fn collect_validation_errors(&self, _errors: &mut ValidationErrors) {}
+    fn validate(&self) -> Result<()> {
+        let mut errors = ValidationErrors::new();
+        self.collect_validation_errors(&mut errors);
+        errors.into_result()
+    }
     fn check_relations_sync(&self) -> Result<()>;
 
+    fn indexes() -> Vec<IndexModel> {
+        Vec::new()
+    }
+    fn sync_indexes_sync(drop_undeclared: bool) -> Result<IndexSyncReport> {
+        index::sync_indexes(
+            &Self::database()?,
+            &Self::collection()?,
+            Self::indexes(),
+            drop_undeclared,
+        )
+    }
+
+    fn query() -> Query<Self> {
+        Query::new()
+    }
+    /// Queries soft deleted documents along with the others.
+    fn with_deleted() -> Query<Self> {
+        Query::new().with_deleted()
+    }
+    /// Queries soft deleted documents only.
+    fn only_deleted() -> Query<Self> {
+        Query::new().only_deleted()
+    }
+
+    /// Uses collection metadata unless the model is soft deleted, in which case it counts the
+    /// documents which aren't.
     fn estimated_document_count_sync() -> Result<i64> {
+        if Self::soft_delete().is_some() {
+            return Self::count_documents_sync(None);
+        }
         Ok(Self::collection()?.estimated_document_count(None)?)
     }
     fn count_documents_sync<F>(filter: F) -> Result<i64>
     where
-        F: Into<Option<Document>>,
+        F: IntoFilter<Self>,
     {
-        Ok(Self::collection()?.count_documents(filter, None)?)
+        Self::count_documents_with_options_sync(filter, None)
+    }
+    fn count_documents_with_options_sync<F, O>(filter: F, options: O) -> Result<i64>
+    where
+        F: IntoFilter<Self>,
+        O: Into<Option<CountOptions>>,
+    {
+        scope::count::<Self>(filter.into_filter()?, options.into(), Scope::Active)
     }
 
     fn find_sync<F, L, S>(filter: F, limit: L, skip: S) -> Result<Vec<Self>>
     where
-        F: Into<Option<Document>>,
+        F: IntoFilter<Self>,
         L: Into<Option<usize>>,
         S: Into<Option<usize>>,
     {
-        let iter = Self::collection()?.find(filter, None)?.map(|r| match r {
-            Ok(d) => bson::from_bson(d.into()).map_err(|e| e.into()),
-            Err(e) => Err(e.into()),
-        });
-        match (limit.into(), skip.into()) {
-            (Some(l), Some(s)) => iter.skip(s).take(l).collect(),
-            (Some(l), None) => iter.take(l).collect(),
-            (None, Some(s)) => iter.skip(s).collect(),
-            (None, None) => iter.collect(),
-        }
+        Self::find_with_options_sync(filter, find_options(limit.into(), skip.into()))
+    }
+    fn find_with_options_sync<F, O>(filter: F, options: O) -> Result<Vec<Self>>
+    where
+        F: IntoFilter<Self>,
+        O: Into<Option<FindOptions>>,
+    {
+        Self::find_iter_with_options_sync(filter, options)?.collect()
+    }
+    fn find_iter_sync<F>(filter: F) -> Result<ModelCursor<Self>>
+    where
+        F: IntoFilter<Self>,
+    {
+        Self::find_iter_with_options_sync(filter, None)
+    }
+    fn find_iter_with_options_sync<F, O>(filter: F, options: O) -> Result<ModelCursor<Self>>
+    where
+        F: IntoFilter<Self>,
+        O: Into<Option<FindOptions>>,
+    {
+        scope::find_iter(filter.into_filter()?, options.into(), Scope::Active)
     }
     fn find_one_sync<F>(filter: F) -> Result<Option<Self>>
     where
-        F: Into<Option<Document>>,
+        F: IntoFilter<Self>,
+    {
+        Self::find_one_with_options_sync(filter, None)
+    }
+    fn find_one_with_options_sync<F, O>(filter: F, options: O) -> Result<Option<Self>>
+    where
+        F: IntoFilter<Self>,
+        O: Into<Option<FindOneOptions>>,
     {
-        Ok(Self::collection()?
-            .find_one(filter, None)?
-            .map(|v| bson::from_bson(v.into()))
-            .transpose()?)
+        scope::find_one(filter.into_filter()?, options.into(), Scope::Active)
     }
     fn find_by_id_sync(id: Self::Id) -> Result<Option<Self>> {
         Self::find_one_sync(doc! {"_id": id.into()})
     }
 
+    fn insert_one_sync(doc: &Self) -> Result<InsertOneResult> {
+        doc.validate()?;
+        Ok(Self::collection()?.insert_one(insert_document(doc)?, None)?)
+    }
     fn insert_many_sync(docs: &[Self]) -> Result<InsertManyResult> {
-        Ok(Self::collection()?.insert_many(to_documents(docs)?, None)?)
+        for doc in docs {
+            doc.validate()?;
+        }
+        let docs = docs
+            .iter()
+            .map(insert_document)
+            .collect::<Result<Vec<_>>>()?;
+        Ok(Self::collection()?.insert_many(docs, None)?)
     }
     fn update_many_sync<Q, U>(query: Q, update: U) -> Result<UpdateResult>
     where
-        Q: Into<Document>,
-        U: Into<UpdateModifications>,
+        Q: IntoQuery<Self>,
+        U: IntoUpdate<Self>,
+    {
+        scope::update_many::<Self>(query.into_query()?, update.into_update()?, Scope::Active)
+    }
+    fn update_one_sync<Q, U>(query: Q, update: U) -> Result<UpdateResult>
+    where
+        Q: IntoQuery<Self>,
+        U: IntoUpdate<Self>,
+    {
+        let query = scope::scoped::<Self>(Some(query.into_query()?), Scope::Active);
+        let update = timestamped_update::<Self>(update.into_update()?);
+        Ok(Self::collection()?.update_one(query.unwrap_or_default(), update, None)?)
+    }
+    fn find_one_and_update_sync<Q, U>(query: Q, update: U) -> Result<Option<Self>>
+    where
+        Q: IntoQuery<Self>,
+        U: IntoUpdate<Self>,
     {
-        Ok(Self::collection()?.update_many(query.into(), update.into(), None)?)
+        Self::find_one_and_update_with_options_sync(query, update, None)
+    }
+    fn find_one_and_update_with_options_sync<Q, U, O>(
+        query: Q,
+        update: U,
+        options: O,
+    ) -> Result<Option<Self>>
+    where
+        Q: IntoQuery<Self>,
+        U: IntoUpdate<Self>,
+        O: Into<Option<FindOneAndUpdateOptions>>,
+    {
+        let query = scope::scoped::<Self>(Some(query.into_query()?), Scope::Active);
+        let update = timestamped_update::<Self>(update.into_update()?);
+        Self::collection()?
+            .find_one_and_update(query.unwrap_or_default(), update, options)?
+            .map(from_document)
+            .transpose()
+    }
+    fn replace_one_sync<Q>(query: Q, replacement: &Self) -> Result<UpdateResult>
+    where
+        Q: IntoQuery<Self>,
+    {
+        replacement.validate()?;
+        let query = scope::scoped::<Self>(Some(query.into_query()?), Scope::Active);
+        let replacement = to_document(replacement)?;
+        replace_document::<Self>(
+            &Self::collection()?,
+            query.unwrap_or_default(),
+            replacement,
+            false,
+        )
+    }
+    fn find_one_and_replace_sync<Q>(query: Q, replacement: &Self) -> Result<Option<Self>>
+    where
+        Q: IntoQuery<Self>,
+    {
+        Self::find_one_and_replace_with_options_sync(query, replacement, None)
+    }
+    fn find_one_and_replace_with_options_sync<Q, O>(
+        query: Q,
+        replacement: &Self,
+        options: O,
+    ) -> Result<Option<Self>>
+    where
+        Q: IntoQuery<Self>,
+        O: Into<Option<FindOneAndReplaceOptions>>,
+    {
+        replacement.validate()?;
+        let query = scope::scoped::<Self>(Some(query.into_query()?), Scope::Active);
+        let replacement = to_document(replacement)?;
+        find_and_replace_document::<Self>(
+            &Self::collection()?,
+            query.unwrap_or_default(),
+            replacement,
+            options.into(),
+        )?
+        .map(from_document)
+        .transpose()
+    }
+    fn delete_one_sync<Q>(query: Q) -> Result<DeleteResult>
+    where
+        Q: IntoQuery<Self>,
+    {
+        match Self::soft_delete() {
+            Some(key) => {
+                let query = scope::deletable::<Self>(query.into_query()?, Scope::Active, key);
+                let result = Self::update_one_sync(query, scope::deletion(key))?;
+                Ok(DeleteResult {
+                    deleted_count: result.modified_count,
+                })
+            }
+            None => Ok(Self::collection()?.delete_one(query.into_query()?, None)?),
+        }
     }
     fn delete_many_sync<Q>(query: Q) -> Result<DeleteResult>
     where
-        Q: Into<Document>,
+        Q: IntoQuery<Self>,
+    {
+        scope::delete_many::<Self>(query.into_query()?, Scope::Active)
+    }
+    fn find_one_and_delete_sync<Q>(query: Q) -> Result<Option<Self>>
+    where
+        Q: IntoQuery<Self>,
     {
-        Ok(Self::collection()?.delete_many(query.into(), None)?)
+        Self::find_one_and_delete_with_options_sync(query, None)
     }
+    fn find_one_and_delete_with_options_sync<Q, O>(query: Q, options: O) -> Result<Option<Self>>
+    where
+        Q: IntoQuery<Self>,
+        O: Into<Option<FindOneAndDeleteOptions>>,
+    {
+        let options = options.into();
+        if let Some(key) = Self::soft_delete() {
+            let query = scope::deletable::<Self>(query.into_query()?, Scope::Active, key);
+            let options = options.map(|o| FindOneAndUpdateOptions {
+                collation: o.collation,
+                max_time: o.max_time,
+                projection: o.projection,
+                sort: o.sort,
+                write_concern: o.write_concern,
+                ..Default::default()
+            });
+            return Self::find_one_and_update_with_options_sync(
+                query,
+                scope::deletion(key),
+                options,
+            );
+        }
 
-    fn save_sync(&self) -> Result<UpdateResult> {
-        self.check_relations_sync()?;
+        Self::collection()?
+            .find_one_and_delete(query.into_query()?, options)?
+            .map(from_document)
+            .transpose()
+    }
 
-        Ok(Self::collection()?.replace_one(
-            self.id_query(),
-            to_document(&self)?,
-            ReplaceOptions {
-                bypass_document_validation: None,
-                upsert: Some(true),
-                collation: None,
-                hint: None,
-                write_concern: None,
-            },
-        )?)
+    /// Upserts the model. Versioned models are saved through `save_versioned_sync` instead, and
+    /// fail with `Error::Versioned` here.
+    fn save_sync(&self) -> Result<UpdateResult> {
+        if self.version().is_some() {
+            return Err(Error::Versioned(self.id_query()));
+        }
+        save_model_sync(self)
+    }
+    /// Upserts the model, bumping its version. A versioned model is only saved over the stored
+    /// document with the same version, and fails with `Error::VersionConflict` if it was saved
+    /// with a newer version meanwhile. Same as `save_sync` for other models.
+    fn save_versioned_sync(&mut self) -> Result<UpdateResult> {
+        let result = save_model_sync(self)?;
+        if let Some((_, current)) = self.version() {
+            self.set_version(current + 1);
+        }
+        Ok(result)
     }
+    /// Deletes the model, only setting its deletion timestamp if it is soft deleted.
     fn remove_sync(&self) -> Result<DeleteResult> {
-        Ok(Self::collection()?.delete_one(self.id_query(), None)?)
+        self.before_remove_sync()?;
+        let result = Self::delete_one_sync(self.id_query())?;
+        self.after_remove_sync()?;
+        Ok(result)
+    }
+    /// Deletes the model for good, even if it is soft deleted.
+    fn force_remove_sync(&self) -> Result<DeleteResult> {
+        self.before_remove_sync()?;
+        let result = Self::collection()?.delete_one(self.id_query(), None)?;
+        self.after_remove_sync()?;
+        Ok(result)
+    }
+    /// Clears the deletion timestamp of a soft deleted model, doing nothing for other models.
+    fn restore_sync(&self) -> Result<UpdateResult> {
+        match Self::soft_delete() {
+            Some(key) => Self::update_one_sync(
+                scope::scoped::<Self>(Some(self.id_query()), Scope::OnlyDeleted)
+                    .unwrap_or_default(),
+                scope::restoration(key),
+            ),
+            None => Ok(UpdateResult {
+                matched_count: 0,
+                modified_count: 0,
+                upserted_id: None,
+            }),
+        }
     }
 }
 
@@ -116,93 +419,476 @@ use async_trait::async_trait;
 #[cfg(feature = "async")]
 use tokio::task::spawn_blocking;
 
+/// Runs a synchronous driver call on the Tokio blocking pool.
+///
+/// The 0.9 driver has no async API, so every async operation, generated ones included, goes
+/// through here until the crate moves to an async driver.
+#[cfg(feature = "async")]
+#[doc(hidden)]
+pub async fn run_blocking<F, T>(f: F) -> Result<T>
+where
+    F: FnOnce() -> Result<T> + Send + 'static,
+    T: Send + 'static,
+{
+    spawn_blocking(f).await?
+}
+
 #[cfg(feature = "async")]
 #[cfg_attr(feature = "async", async_trait)]
-pub trait Model: BlockingModel + Send + Sync + 'static {
+pub trait Model: BlockingModel + Hooks + Send + Sync + 'static {
     async fn check_relations(&self) -> Result<()>;
 
+    /// Runs the asynchronous custom validators, adding every failure to `errors`.
+    async fn collect_validation_errors_async(&self, _errors: &mut ValidationErrors) {}
+    /// Runs every validator, asynchronous ones included, which `validate` skips.
+    async fn validate_async(&self) -> Result<()> {
+        let mut errors = ValidationErrors::new();
+        self.collect_validation_errors(&mut errors);
+        self.collect_validation_errors_async(&mut errors).await;
+        errors.into_result()
+    }
+
+    async fn sync_indexes(drop_undeclared: bool) -> Result<IndexSyncReport> {
+        run_blocking(move || Self::sync_indexes_sync(drop_undeclared)).await
+    }
+
     async fn estimated_document_count() -> Result<i64> {
-        spawn_blocking(Self::estimated_document_count_sync).await?
+        run_blocking(Self::estimated_document_count_sync).await
     }
     async fn count_documents<F>(filter: F) -> Result<i64>
     where
-        F: Into<Option<Document>> + Send + 'static,
+        F: IntoFilter<Self> + Send + 'static,
+    {
+        run_blocking(move || Self::count_documents_sync(filter)).await
+    }
+    async fn count_documents_with_options<F, O>(filter: F, options: O) -> Result<i64>
+    where
+        F: IntoFilter<Self> + Send + 'static,
+        O: Into<Option<CountOptions>> + Send + 'static,
     {
-        spawn_blocking(move || Self::count_documents_sync(filter)).await?
+        run_blocking(move || Self::count_documents_with_options_sync(filter, options)).await
     }
 
     async fn find<F, L, S>(filter: F, limit: L, skip: S) -> Result<Vec<Self>>
     where
-        F: Into<Option<Document>> + Send + 'static,
+        F: IntoFilter<Self> + Send + 'static,
         L: Into<Option<usize>> + Send + 'static,
         S: Into<Option<usize>> + Send + 'static,
     {
-        spawn_blocking(move || Self::find_sync(filter, limit, skip)).await?
+        Self::find_with_options(filter, find_options(limit.into(), skip.into())).await
+    }
+    async fn find_with_options<F, O>(filter: F, options: O) -> Result<Vec<Self>>
+    where
+        F: IntoFilter<Self> + Send + 'static,
+        O: Into<Option<FindOptions>> + Send + 'static,
+    {
+        after_load_all(run_blocking(move || Self::find_with_options_sync(filter, options)).await?)
+            .await
+    }
+    async fn find_stream<F>(filter: F) -> Result<ModelStream<Self>>
+    where
+        F: IntoFilter<Self> + Send + 'static,
+    {
+        Self::find_stream_with_options(filter, None).await
+    }
+    async fn find_stream_with_options<F, O>(filter: F, options: O) -> Result<ModelStream<Self>>
+    where
+        F: IntoFilter<Self> + Send + 'static,
+        O: Into<Option<FindOptions>> + Send + 'static,
+    {
+        let cursor =
+            run_blocking(move || Self::find_iter_with_options_sync(filter, options)).await?;
+        Ok(ModelStream::new(cursor))
     }
     async fn find_one<F>(filter: F) -> Result<Option<Self>>
     where
-        F: Into<Option<Document>> + Send + 'static,
+        F: IntoFilter<Self> + Send + 'static,
+    {
+        after_load_one(run_blocking(move || Self::find_one_sync(filter)).await?).await
+    }
+    async fn find_one_with_options<F, O>(filter: F, options: O) -> Result<Option<Self>>
+    where
+        F: IntoFilter<Self> + Send + 'static,
+        O: Into<Option<FindOneOptions>> + Send + 'static,
     {
-        spawn_blocking(move || Self::find_one_sync(filter)).await?
+        after_load_one(
+            run_blocking(move || Self::find_one_with_options_sync(filter, options)).await?,
+        )
+        .await
     }
     async fn find_by_id(id: Self::Id) -> Result<Option<Self>> {
-        spawn_blocking(move || Self::find_by_id_sync(id)).await?
+        after_load_one(run_blocking(move || Self::find_by_id_sync(id)).await?).await
     }
 
+    async fn insert_one(doc: &Self) -> Result<InsertOneResult> {
+        doc.validate_async().await?;
+        let doc = insert_document(doc)?;
+        run_blocking(move || Ok(Self::collection()?.insert_one(doc, None)?)).await
+    }
     async fn insert_many(docs: &[Self]) -> Result<InsertManyResult> {
-        let docs = to_documents(docs)?;
-        spawn_blocking(move || Ok(Self::collection()?.insert_many(docs, None)?)).await?
+        for doc in docs {
+            doc.validate_async().await?;
+        }
+        let docs = docs
+            .iter()
+            .map(insert_document)
+            .collect::<Result<Vec<_>>>()?;
+        run_blocking(move || Ok(Self::collection()?.insert_many(docs, None)?)).await
     }
     async fn update_many<Q, U>(query: Q, update: U) -> Result<UpdateResult>
     where
-        Q: Into<Document> + Send + 'static,
-        U: Into<UpdateModifications> + Send + 'static,
+        Q: IntoQuery<Self> + Send + 'static,
+        U: IntoUpdate<Self> + Send + 'static,
     {
-        spawn_blocking(move || Ok(Self::update_many_sync(query.into(), update.into())?)).await?
+        run_blocking(move || Self::update_many_sync(query, update)).await
+    }
+    async fn update_one<Q, U>(query: Q, update: U) -> Result<UpdateResult>
+    where
+        Q: IntoQuery<Self> + Send + 'static,
+        U: IntoUpdate<Self> + Send + 'static,
+    {
+        run_blocking(move || Self::update_one_sync(query, update)).await
+    }
+    async fn find_one_and_update<Q, U>(query: Q, update: U) -> Result<Option<Self>>
+    where
+        Q: IntoQuery<Self> + Send + 'static,
+        U: IntoUpdate<Self> + Send + 'static,
+    {
+        Self::find_one_and_update_with_options(query, update, None).await
+    }
+    async fn find_one_and_update_with_options<Q, U, O>(
+        query: Q,
+        update: U,
+        options: O,
+    ) -> Result<Option<Self>>
+    where
+        Q: IntoQuery<Self> + Send + 'static,
+        U: IntoUpdate<Self> + Send + 'static,
+        O: Into<Option<FindOneAndUpdateOptions>> + Send + 'static,
+    {
+        let found = run_blocking(move || {
+            Self::find_one_and_update_with_options_sync(query, update, options)
+        })
+        .await?;
+        after_load_one(found).await
+    }
+    async fn replace_one<Q>(query: Q, replacement: &Self) -> Result<UpdateResult>
+    where
+        Q: IntoQuery<Self> + Send + 'static,
+    {
+        replacement.validate_async().await?;
+        let replacement = to_document(replacement)?;
+        run_blocking(move || {
+            let query = scope::scoped::<Self>(Some(query.into_query()?), Scope::Active);
+            replace_document::<Self>(
+                &Self::collection()?,
+                query.unwrap_or_default(),
+                replacement,
+                false,
+            )
+        })
+        .await
+    }
+    async fn find_one_and_replace<Q>(query: Q, replacement: &Self) -> Result<Option<Self>>
+    where
+        Q: IntoQuery<Self> + Send + 'static,
+    {
+        Self::find_one_and_replace_with_options(query, replacement, None).await
+    }
+    async fn find_one_and_replace_with_options<Q, O>(
+        query: Q,
+        replacement: &Self,
+        options: O,
+    ) -> Result<Option<Self>>
+    where
+        Q: IntoQuery<Self> + Send + 'static,
+        O: Into<Option<FindOneAndReplaceOptions>> + Send + 'static,
+    {
+        replacement.validate_async().await?;
+        let replacement = to_document(replacement)?;
+        let found = run_blocking(move || {
+            let query = scope::scoped::<Self>(Some(query.into_query()?), Scope::Active);
+            find_and_replace_document::<Self>(
+                &Self::collection()?,
+                query.unwrap_or_default(),
+                replacement,
+                options.into(),
+            )?
+            .map(from_document)
+            .transpose()
+        })
+        .await?;
+        after_load_one(found).await
+    }
+    async fn delete_one<Q>(query: Q) -> Result<DeleteResult>
+    where
+        Q: IntoQuery<Self> + Send + 'static,
+    {
+        run_blocking(move || Self::delete_one_sync(query)).await
     }
     async fn delete_many<Q>(query: Q) -> Result<DeleteResult>
     where
-        Q: Into<Document> + Send + 'static,
+        Q: IntoQuery<Self> + Send + 'static,
+    {
+        run_blocking(move || Self::delete_many_sync(query)).await
+    }
+    async fn find_one_and_delete<Q>(query: Q) -> Result<Option<Self>>
+    where
+        Q: IntoQuery<Self> + Send + 'static,
+    {
+        Self::find_one_and_delete_with_options(query, None).await
+    }
+    async fn find_one_and_delete_with_options<Q, O>(query: Q, options: O) -> Result<Option<Self>>
+    where
+        Q: IntoQuery<Self> + Send + 'static,
+        O: Into<Option<FindOneAndDeleteOptions>> + Send + 'static,
     {
-        spawn_blocking(move || Self::delete_many_sync(query)).await?
+        let found =
+            run_blocking(move || Self::find_one_and_delete_with_options_sync(query, options))
+                .await?;
+        after_load_one(found).await
     }
 
     async fn save(&self) -> Result<UpdateResult> {
-        self.check_relations().await?;
+        if self.version().is_some() {
+            return Err(Error::Versioned(self.id_query()));
+        }
+        save_model(self).await
+    }
+    async fn save_versioned(&mut self) -> Result<UpdateResult> {
+        let result = save_model(self).await?;
+        if let Some((_, current)) = self.version() {
+            self.set_version(current + 1);
+        }
+        Ok(result)
+    }
+    async fn remove(&self) -> Result<DeleteResult> {
+        self.before_remove_sync()?;
+        self.before_remove().await?;
 
         let query = self.id_query();
-        let replacement = to_document(self)?;
-        spawn_blocking(move || {
-            Ok(Self::collection()?.replace_one(
-                query,
-                replacement,
-                ReplaceOptions {
-                    bypass_document_validation: None,
-                    upsert: Some(true),
-                    collation: None,
-                    hint: None,
-                    write_concern: None,
-                },
-            )?)
-        })
-        .await?
+        let result = run_blocking(move || Self::delete_one_sync(query)).await?;
+        self.after_remove_sync()?;
+        self.after_remove().await?;
+        Ok(result)
     }
-    async fn remove(&self) -> Result<DeleteResult> {
+    async fn force_remove(&self) -> Result<DeleteResult> {
+        self.before_remove_sync()?;
+        self.before_remove().await?;
+
         let query = self.id_query();
-        spawn_blocking(move || Ok(Self::collection()?.delete_one(query, None)?)).await?
+        let result = run_blocking(move || Ok(Self::collection()?.delete_one(query, None)?)).await?;
+        self.after_remove_sync()?;
+        self.after_remove().await?;
+        Ok(result)
+    }
+    async fn restore(&self) -> Result<UpdateResult> {
+        let query = scope::scoped::<Self>(Some(self.id_query()), Scope::OnlyDeleted);
+        let query = query.unwrap_or_default();
+        match Self::soft_delete() {
+            Some(key) => {
+                run_blocking(move || Self::update_one_sync(query, scope::restoration(key))).await
+            }
+            None => Ok(UpdateResult {
+                matched_count: 0,
+                modified_count: 0,
+                upserted_id: None,
+            }),
+        }
+    }
+}
+
+/// Validates and saves a model, running the save hooks around it.
+fn save_model_sync<M: BlockingModel>(m: &M) -> Result<UpdateResult> {
+    m.validate()?;
+    m.check_relations_sync()?;
+    m.before_save_sync()?;
+
+    let result = save_document::<M>(
+        &M::collection()?,
+        m.id_query(),
+        to_document(m)?,
+        m.version(),
+    )?;
+    m.after_save_sync()?;
+    Ok(result)
+}
+/// Async form of `save_model_sync`, running both forms of the hooks.
+#[cfg(feature = "async")]
+async fn save_model<M: Model>(m: &M) -> Result<UpdateResult> {
+    m.validate_async().await?;
+    m.check_relations().await?;
+    m.before_save_sync()?;
+    m.before_save().await?;
+
+    let query = m.id_query();
+    let replacement = to_document(m)?;
+    let version = m.version();
+    let result =
+        run_blocking(move || save_document::<M>(&M::collection()?, query, replacement, version))
+            .await?;
+    m.after_save_sync()?;
+    m.after_save().await?;
+    Ok(result)
+}
+
+/// Runs the async `after_load` hook on documents already loaded by a blocking find.
+#[cfg(feature = "async")]
+pub(crate) async fn after_load_all<M: Model>(mut docs: Vec<M>) -> Result<Vec<M>> {
+    for doc in &mut docs {
+        doc.after_load().await?;
+    }
+    Ok(docs)
+}
+#[cfg(feature = "async")]
+pub(crate) async fn after_load_one<M: Model>(doc: Option<M>) -> Result<Option<M>> {
+    match doc {
+        Some(mut doc) => {
+            doc.after_load().await?;
+            Ok(Some(doc))
+        }
+        None => Ok(None),
+    }
+}
+
+fn find_options(limit: Option<usize>, skip: Option<usize>) -> FindOptions {
+    FindOptions {
+        limit: limit.map(|l| l as i64),
+        skip: skip.map(|s| s as i64),
+        ..Default::default()
     }
 }
 
-fn to_documents<T: Serialize>(docs: &[T]) -> Result<Vec<Document>> {
-    docs.iter()
-        .map(|s| match bson::to_bson(s) {
-            Ok(b) => match b {
-                Bson::Document(d) => Ok(d),
-                _ => unreachable!(),
+/// Encodes a model about to be inserted, setting its timestamps.
+fn insert_document<M: BlockingModel>(m: &M) -> Result<Document> {
+    let mut doc = to_document(m)?;
+    if let Some(timestamps) = M::timestamps() {
+        timestamps.insert(&mut doc);
+    }
+    Ok(doc)
+}
+/// Replaces a stored document, through a pipeline update maintaining the timestamps if the model
+/// has any. Inserts the replacement when nothing matches if `upsert` is set.
+fn replace_document<M: BlockingModel>(
+    collection: &Collection,
+    query: Document,
+    replacement: Document,
+    upsert: bool,
+) -> Result<UpdateResult> {
+    let upsert = Some(upsert);
+    Ok(match M::timestamps() {
+        Some(timestamps) => collection.update_one(
+            query,
+            timestamps.replace(replacement),
+            UpdateOptions {
+                upsert,
+                ..Default::default()
             },
-            Err(e) => Err(e.into()),
-        })
-        .collect()
+        )?,
+        None => collection.replace_one(
+            query,
+            replacement,
+            ReplaceOptions {
+                bypass_document_validation: None,
+                upsert,
+                collation: None,
+                hint: None,
+                write_concern: None,
+            },
+        )?,
+    })
+}
+/// Same as `replace_document`, returning the replaced or replacing document.
+fn find_and_replace_document<M: BlockingModel>(
+    collection: &Collection,
+    query: Document,
+    replacement: Document,
+    options: Option<FindOneAndReplaceOptions>,
+) -> Result<Option<Document>> {
+    let timestamps = match M::timestamps() {
+        Some(timestamps) => timestamps,
+        None => return Ok(collection.find_one_and_replace(query, replacement, options)?),
+    };
+
+    let options = options.map(|o| FindOneAndUpdateOptions {
+        array_filters: None,
+        bypass_document_validation: o.bypass_document_validation,
+        max_time: o.max_time,
+        projection: o.projection,
+        return_document: o.return_document,
+        sort: o.sort,
+        upsert: o.upsert,
+        write_concern: o.write_concern,
+        collation: o.collation,
+    });
+    Ok(collection.find_one_and_update(query, timestamps.replace(replacement), options)?)
+}
+pub(crate) fn timestamped_update<M: BlockingModel>(
+    update: UpdateModifications,
+) -> UpdateModifications {
+    match M::timestamps() {
+        Some(timestamps) => timestamps.update(update),
+        None => update,
+    }
+}
+
+/// Server error code returned when a write breaks a unique index.
+const DUPLICATE_KEY: i32 = 11000;
+
+/// Upserts a saved model as a replacement, maintaining its timestamps and version.
+///
+/// A versioned save replaces the stored document with the same version and no other. When none
+/// matches, the document is inserted if no document with its id exists, and the save fails with
+/// `Error::VersionConflict` otherwise.
+fn save_document<M: BlockingModel>(
+    collection: &Collection,
+    id_query: Document,
+    mut doc: Document,
+    version: Option<(&'static str, i64)>,
+) -> Result<UpdateResult> {
+    let timestamps = M::timestamps();
+    let (key, current) = match version {
+        Some(version) => version,
+        None => return replace_document::<M>(collection, id_query, doc, true),
+    };
+
+    let mut query = id_query.clone();
+    query.insert(key, current);
+    doc.insert(key, current + 1);
+    let result = replace_document::<M>(collection, query, doc.clone(), false)?;
+    if result.matched_count > 0 {
+        return Ok(result);
+    }
+    if collection.count_documents(id_query.clone(), None)? > 0 {
+        return Err(Error::VersionConflict(id_query));
+    }
+
+    if let Some(timestamps) = timestamps {
+        timestamps.insert(&mut doc);
+    }
+    match collection.insert_one(doc, None) {
+        Ok(r) => Ok(UpdateResult {
+            matched_count: 0,
+            modified_count: 0,
+            upserted_id: Some(r.inserted_id),
+        }),
+        // Another save inserted the document in the meantime, unless another unique index broke.
+        Err(e) => match e.kind.as_ref() {
+            ErrorKind::WriteError(WriteFailure::WriteError(w))
+                if w.code == DUPLICATE_KEY
+                    && collection.count_documents(id_query.clone(), None)? > 0 =>
+            {
+                Err(Error::VersionConflict(id_query))
+            }
+            _ => Err(e.into()),
+        },
+    }
+}
+/// Decodes a document returned by the server and runs the `after_load_sync` hook on it.
+pub(crate) fn from_document<M: BlockingModel>(doc: Document) -> Result<M> {
+    let mut m: M = bson::from_bson(doc.into())?;
+    m.after_load_sync()?;
+    Ok(m)
 }
 fn to_document<T: Serialize>(m: &T) -> Result<Document> {
     match bson::to_bson(m)? {
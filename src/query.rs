@@ -0,0 +1,215 @@
+use crate::{
+    scope::{self, Scope},
+    BlockingModel, Error, IntoQuery, IntoUpdate, ModelCursor, Result,
+};
+use bson::{Bson, Document};
+use mongodb::{
+    options::{CountOptions, FindOneOptions, FindOptions, Hint},
+    results::{DeleteResult, UpdateResult},
+};
+use std::marker::PhantomData;
+
+/// Chainable query on a model, created with `BlockingModel::query`.
+///
+/// Filters given across several calls must all match, a key constrained twice being combined with
+/// `$and`. Sorts are merged, later keys taking precedence over earlier ones.
+pub struct Query<M> {
+    filter: Document,
+    options: FindOptions,
+    scope: Scope,
+    /// First filter which couldn't be built, reported when the query runs.
+    invalid: Option<Error>,
+    model: PhantomData<fn() -> M>,
+}
+
+impl<M> Query<M> {
+    pub(crate) fn new() -> Self {
+        Self {
+            filter: Document::new(),
+            options: FindOptions::default(),
+            scope: Scope::Active,
+            invalid: None,
+            model: PhantomData,
+        }
+    }
+
+    pub fn filter<F: IntoQuery<M>>(mut self, filter: F) -> Self {
+        match filter.into_query() {
+            Ok(filter) => {
+                for (key, value) in filter {
+                    add_condition(&mut self.filter, key, value);
+                }
+            }
+            Err(e) => {
+                self.invalid.get_or_insert(e);
+            }
+        }
+        self
+    }
+    pub fn sort<S: Into<Document>>(mut self, sort: S) -> Self {
+        match &mut self.options.sort {
+            Some(s) => s.extend(sort.into()),
+            None => self.options.sort = Some(sort.into()),
+        }
+        self
+    }
+    pub fn project<P: Into<Document>>(mut self, projection: P) -> Self {
+        self.options.projection = Some(projection.into());
+        self
+    }
+    pub fn limit(mut self, limit: i64) -> Self {
+        self.options.limit = Some(limit);
+        self
+    }
+    pub fn skip(mut self, skip: i64) -> Self {
+        self.options.skip = Some(skip);
+        self
+    }
+    pub fn batch_size(mut self, batch_size: u32) -> Self {
+        self.options.batch_size = Some(batch_size);
+        self
+    }
+    pub fn hint(mut self, hint: Hint) -> Self {
+        self.options.hint = Some(hint);
+        self
+    }
+    /// Includes soft deleted documents, which are skipped by default.
+    pub fn with_deleted(mut self) -> Self {
+        self.scope = Scope::WithDeleted;
+        self
+    }
+    /// Only matches soft deleted documents, matching nothing if the model isn't soft deleted.
+    pub fn only_deleted(mut self) -> Self {
+        self.scope = Scope::OnlyDeleted;
+        self
+    }
+
+    pub fn filter_document(&self) -> &Document {
+        &self.filter
+    }
+    pub fn options(&self) -> &FindOptions {
+        &self.options
+    }
+
+    /// Fails with the error of the first filter which couldn't be built.
+    fn checked(mut self) -> Result<Self> {
+        match self.invalid.take() {
+            Some(e) => Err(e),
+            None => Ok(self),
+        }
+    }
+
+    fn filter_option(&self) -> Option<Document> {
+        if self.filter.is_empty() {
+            None
+        } else {
+            Some(self.filter.clone())
+        }
+    }
+}
+
+impl<M: BlockingModel> Query<M> {
+    pub fn exec_sync(self) -> Result<Vec<M>> {
+        self.iter_sync()?.collect()
+    }
+    pub fn iter_sync(self) -> Result<ModelCursor<M>> {
+        let query = self.checked()?;
+        scope::find_iter(query.filter_option(), Some(query.options), query.scope)
+    }
+    /// Returns the first matching document, honoring sort, skip and projection.
+    pub fn first_sync(self) -> Result<Option<M>> {
+        let query = self.checked()?;
+        let options = FindOneOptions {
+            hint: query.options.hint,
+            projection: query.options.projection,
+            skip: query.options.skip,
+            sort: query.options.sort,
+            ..Default::default()
+        };
+        scope::find_one(Some(query.filter), Some(options), query.scope)
+    }
+    /// Counts matching documents, honoring limit, skip and hint.
+    pub fn count_sync(self) -> Result<i64> {
+        let query = self.checked()?;
+        let options = CountOptions {
+            hint: query.options.hint,
+            limit: query.options.limit,
+            skip: query.options.skip,
+            ..Default::default()
+        };
+        scope::count::<M>(Some(query.filter), Some(options), query.scope)
+    }
+    /// Deletes every matching document, ignoring everything but the filter and the soft deletion
+    /// scope.
+    pub fn delete_sync(self) -> Result<DeleteResult> {
+        let query = self.checked()?;
+        scope::delete_many::<M>(query.filter, query.scope)
+    }
+    /// Updates every matching document, ignoring everything but the filter and the soft deletion
+    /// scope.
+    pub fn update_sync<U>(self, update: U) -> Result<UpdateResult>
+    where
+        U: IntoUpdate<M>,
+    {
+        let query = self.checked()?;
+        scope::update_many::<M>(query.filter, update.into_update()?, query.scope)
+    }
+}
+
+#[cfg(feature = "async")]
+use crate::{after_load_all, after_load_one, run_blocking, Model, ModelStream};
+
+#[cfg(feature = "async")]
+impl<M: Model> Query<M> {
+    pub async fn exec(self) -> Result<Vec<M>> {
+        after_load_all(run_blocking(move || self.exec_sync()).await?).await
+    }
+    pub async fn stream(self) -> Result<ModelStream<M>> {
+        let cursor = run_blocking(move || self.iter_sync()).await?;
+        Ok(ModelStream::new(cursor))
+    }
+    pub async fn first(self) -> Result<Option<M>> {
+        after_load_one(run_blocking(move || self.first_sync()).await?).await
+    }
+    pub async fn count(self) -> Result<i64> {
+        run_blocking(move || self.count_sync()).await
+    }
+    pub async fn delete(self) -> Result<DeleteResult> {
+        run_blocking(move || self.delete_sync()).await
+    }
+    pub async fn update<U>(self, update: U) -> Result<UpdateResult>
+    where
+        U: IntoUpdate<M> + Send + 'static,
+    {
+        run_blocking(move || self.update_sync(update)).await
+    }
+}
+
+/// Adds a condition to a filter, moving it into `$and` along with the existing one if the key is
+/// already constrained.
+fn add_condition(filter: &mut Document, key: String, value: Bson) {
+    let previous = match filter.remove(&key) {
+        Some(p) => p,
+        None => {
+            filter.insert(key, value);
+            return;
+        }
+    };
+
+    let mut conditions = match filter.remove("$and") {
+        Some(Bson::Array(a)) => a,
+        Some(other) => vec![other],
+        None => Vec::new(),
+    };
+    for condition in [previous, value] {
+        match condition {
+            Bson::Array(a) if key == "$and" => conditions.extend(a),
+            c => {
+                let mut document = Document::new();
+                document.insert(key.clone(), c);
+                conditions.push(Bson::Document(document));
+            }
+        }
+    }
+    filter.insert("$and", conditions);
+}
@@ -1,8 +1,12 @@
 pub use bson;
+pub use inventory;
 pub use mongodb;
 pub use once_cell;
+pub use regex;
 
 #[cfg(feature = "async")]
 pub use async_trait;
 #[cfg(feature = "async")]
+pub use futures;
+#[cfg(feature = "async")]
 pub use tokio;
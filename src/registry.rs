@@ -0,0 +1,31 @@
+use crate::{IndexSyncReport, Result};
+use mongodb::options::IndexModel;
+
+/// Description of a derived model, registered automatically by the derives.
+#[derive(Clone, Copy, Debug)]
+pub struct ModelInfo {
+    /// Path of the model, like `my_crate::models::User`.
+    pub type_name: &'static str,
+    pub connection: &'static str,
+    pub collection: &'static str,
+    pub indexes: fn() -> Vec<IndexModel>,
+    pub sync_indexes: fn(bool) -> Result<IndexSyncReport>,
+}
+
+inventory::collect!(ModelInfo);
+
+/// Lists every derived model linked into the program, sorted by path.
+pub fn registered_models() -> Vec<ModelInfo> {
+    let mut models: Vec<_> = inventory::iter::<ModelInfo>.into_iter().copied().collect();
+    models.sort_by_key(|m| m.type_name);
+    models
+}
+
+/// Synchronizes the indexes of every registered model, returning the outcome for each of them
+/// so a failing model doesn't prevent the others from being synchronized.
+pub fn sync_all_indexes(drop_undeclared: bool) -> Vec<(ModelInfo, Result<IndexSyncReport>)> {
+    registered_models()
+        .into_iter()
+        .map(|m| (m, (m.sync_indexes)(drop_undeclared)))
+        .collect()
+}
@@ -0,0 +1,261 @@
+#[cfg(feature = "async")]
+use crate::Model;
+use crate::{
+    from_document,
+    scope::{self, Scope},
+    BlockingModel, Error, Result,
+};
+use bson::{doc, Bson, Document};
+use mongodb::options::FindOptions;
+use std::{
+    collections::{HashMap, HashSet},
+    fmt::Display,
+};
+
+/// Loads the documents referenced by a `has_many` field in a single query, in the order of `ids`.
+///
+/// Repeated ids get a document each, decoded again from the loaded one. Fails with an
+/// `Error::Relation` listing every id without a matching document.
+#[doc(hidden)]
+pub fn find_related_sync<M>(ids: &[M::Id]) -> Result<Vec<M>>
+where
+    M: BlockingModel,
+    M::Id: Display,
+{
+    let keys: Vec<String> = ids.iter().map(ToString::to_string).collect();
+    let mut loaded = load_related::<M>(ids)?;
+    let mut remaining: HashMap<&str, usize> = HashMap::new();
+    for key in &keys {
+        *remaining.entry(key).or_default() += 1;
+    }
+
+    let mut result = Vec::with_capacity(keys.len());
+    for key in &keys {
+        let count = remaining.get_mut(key.as_str()).unwrap();
+        *count -= 1;
+        let entry = if *count == 0 {
+            loaded.remove(key).map(|(m, _)| m)
+        } else {
+            loaded
+                .get(key)
+                .map(|(_, document)| from_document(document.clone()))
+                .transpose()?
+        };
+        result.extend(entry);
+    }
+    Ok(result)
+}
+
+/// Async form of `find_related_sync`, also running the async `after_load` hook on every document.
+#[cfg(feature = "async")]
+#[doc(hidden)]
+pub async fn find_related<M>(ids: Vec<M::Id>) -> Result<Vec<M>>
+where
+    M: Model,
+    M::Id: Display + 'static,
+{
+    let found = crate::run_blocking(move || find_related_sync::<M>(&ids)).await?;
+    crate::after_load_all(found).await
+}
+
+/// Loads the documents with the given ids in a single query, keyed by the display form of their
+/// id and along with the document they were decoded from.
+///
+/// Fails with an `Error::Relation` listing every id without a matching document.
+fn load_related<M>(ids: &[M::Id]) -> Result<HashMap<String, (M, Document)>>
+where
+    M: BlockingModel,
+    M::Id: Display,
+{
+    let keys: Vec<Bson> = ids.iter().cloned().map(Into::into).collect();
+    if keys.is_empty() {
+        return Ok(HashMap::new());
+    }
+
+    let filter = scope::scoped::<M>(Some(doc! {"_id": {"$in": keys}}), Scope::Active);
+    let mut loaded = HashMap::with_capacity(ids.len());
+    for document in M::collection()?.find(filter, None)? {
+        let document = document?;
+        let m: M = from_document(document.clone())?;
+        loaded.insert(m.id().to_string(), (m, document));
+    }
+
+    let mut missing = Vec::new();
+    let mut seen = HashSet::new();
+    for id in ids {
+        let key = id.to_string();
+        if !loaded.contains_key(&key) && seen.insert(key.clone()) {
+            missing.push(key);
+        }
+    }
+    if missing.is_empty() {
+        Ok(loaded)
+    } else {
+        Err(missing_error(&missing))
+    }
+}
+
+/// Relation from a model to the documents of `T` it references, built from the `relations`
+/// function generated by the derives.
+///
+/// `R` is what a single document references, such as `T`, `Option<T>` or `Vec<T>` depending on
+/// the relation and its field.
+pub struct Relation<M, T: BlockingModel, R> {
+    ids: fn(&M) -> Vec<T::Id>,
+    resolve: Resolve<M, T, R>,
+}
+
+/// Builds what a document references, given a lookup of the loaded documents by id.
+type Resolve<M, T, R> = fn(&M, &dyn Fn(&<T as BlockingModel>::Id) -> Result<T>) -> Result<R>;
+
+impl<M, T: BlockingModel, R> Clone for Relation<M, T, R> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+impl<M, T: BlockingModel, R> Copy for Relation<M, T, R> {}
+
+impl<M, T: BlockingModel, R> Relation<M, T, R> {
+    #[doc(hidden)]
+    pub fn new(ids: fn(&M) -> Vec<T::Id>, resolve: Resolve<M, T, R>) -> Self {
+        Self { ids, resolve }
+    }
+}
+
+impl<M, T, R> Relation<M, T, R>
+where
+    T: BlockingModel + Clone,
+    T::Id: Display,
+{
+    /// Pairs every document with the ones it references, loading them all in a single query.
+    ///
+    /// Fails with an `Error::Relation` listing every referenced id without a matching document.
+    pub fn populate_sync(&self, documents: Vec<M>) -> Result<Vec<(M, R)>> {
+        let ids: Vec<T::Id> = documents.iter().flat_map(self.ids).collect();
+        let related = load_by_id::<T>(&ids)?;
+        self.resolve_all(documents, &related)
+    }
+
+    fn resolve_all(&self, documents: Vec<M>, related: &HashMap<String, T>) -> Result<Vec<(M, R)>> {
+        let get = |id: &T::Id| {
+            let key = id.to_string();
+            match related.get(&key) {
+                Some(t) => Ok(t.clone()),
+                None => Err(missing_error(&[key])),
+            }
+        };
+
+        documents
+            .into_iter()
+            .map(|m| {
+                let r = (self.resolve)(&m, &get)?;
+                Ok((m, r))
+            })
+            .collect()
+    }
+}
+
+#[cfg(feature = "async")]
+impl<M, T, R> Relation<M, T, R>
+where
+    M: Send + 'static,
+    T: Model + Clone,
+    T::Id: Display,
+    R: Send + 'static,
+{
+    /// Async form of `populate_sync`, also running the async `after_load` hook on every loaded
+    /// document.
+    pub async fn populate(&self, documents: Vec<M>) -> Result<Vec<(M, R)>> {
+        let ids: Vec<T::Id> = documents.iter().flat_map(self.ids).collect();
+        let mut related = crate::run_blocking(move || load_by_id::<T>(&ids)).await?;
+        for t in related.values_mut() {
+            t.after_load().await?;
+        }
+        self.resolve_all(documents, &related)
+    }
+}
+
+/// Loads the documents with the given ids in a single query, keyed by the display form of their
+/// id.
+fn load_by_id<M>(ids: &[M::Id]) -> Result<HashMap<String, M>>
+where
+    M: BlockingModel,
+    M::Id: Display,
+{
+    Ok(load_related::<M>(ids)?
+        .into_iter()
+        .map(|(key, (m, _))| (key, m))
+        .collect())
+}
+
+/// Checks that every reference to a model, given as its id and the id's display form, points to
+/// an existing document, counting them in a single query.
+///
+/// Only when the count falls short are the matching ids loaded, to fail with an `Error::Relation`
+/// listing exactly the dangling ones.
+#[doc(hidden)]
+pub fn check_related_sync<M: BlockingModel>(refs: Vec<(Bson, String)>) -> Result<()> {
+    let mut seen = HashSet::with_capacity(refs.len());
+    let keys: Vec<Bson> = refs
+        .iter()
+        .filter(|(key, _)| seen.insert(key.to_string()))
+        .map(|(key, _)| key.clone())
+        .collect();
+    if keys.is_empty() {
+        return Ok(());
+    }
+
+    let filter = doc! {"_id": {"$in": keys.clone()}};
+    if scope::count::<M>(Some(filter.clone()), None, Scope::Active)? >= keys.len() as i64 {
+        return Ok(());
+    }
+
+    let options = FindOptions::builder().projection(doc! {"_id": 1}).build();
+    let filter = scope::scoped::<M>(Some(filter), Scope::Active);
+    let mut existing = HashSet::new();
+    for document in M::collection()?.find(filter, Some(options))? {
+        if let Some(id) = document?.get("_id") {
+            existing.insert(id.to_string());
+        }
+    }
+
+    let mut missing = Vec::new();
+    let mut reported = HashSet::new();
+    for (key, id) in refs {
+        if !existing.contains(&key.to_string()) && reported.insert(id.clone()) {
+            missing.push(id);
+        }
+    }
+    if missing.is_empty() {
+        Ok(())
+    } else {
+        Err(missing_error(&missing))
+    }
+}
+
+/// Async form of `check_related_sync`, run on the blocking pool when there is anything to check.
+#[cfg(feature = "async")]
+#[doc(hidden)]
+pub async fn check_related<M>(refs: Vec<(Bson, String)>) -> Result<()>
+where
+    M: BlockingModel + Send + 'static,
+{
+    if refs.is_empty() {
+        return Ok(());
+    }
+    crate::run_blocking(move || check_related_sync::<M>(refs)).await
+}
+
+pub(crate) fn missing_error(missing: &[String]) -> Error {
+    if missing.len() == 1 {
+        Error::Relation(format!(
+            "referenced document with id {} doesn't exist",
+            missing[0]
+        ))
+    } else {
+        Error::Relation(format!(
+            "referenced documents with ids {} don't exist",
+            missing.join(", ")
+        ))
+    }
+}
@@ -0,0 +1,292 @@
+use crate::{from_document, timestamped_update, BlockingModel, ModelCursor, Result};
+use bson::{Bson, Document};
+use chrono::Utc;
+use mongodb::{
+    options::{CountOptions, FindOneOptions, FindOptions, UpdateModifications},
+    results::{DeleteResult, UpdateResult},
+};
+
+/// Documents of a model annotated with `#[bongo(soft_delete)]` seen by a read or delete.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(crate) enum Scope {
+    Active,
+    WithDeleted,
+    OnlyDeleted,
+}
+
+/// Restricts a filter to the documents in scope, leaving it untouched if the filter already
+/// constrains the deletion timestamp, even within `$and`, `$or` or `$nor`. Models which aren't soft deleted have no deleted documents,
+/// so only `Scope::OnlyDeleted` changes their filter, into one matching nothing.
+pub(crate) fn scoped<M: BlockingModel>(filter: Option<Document>, scope: Scope) -> Option<Document> {
+    let key = match (M::soft_delete(), scope) {
+        (Some(k), _) => k,
+        (None, Scope::OnlyDeleted) => return Some(nothing()),
+        (None, _) => return filter,
+    };
+    let condition = match scope {
+        Scope::Active => Bson::Null,
+        Scope::WithDeleted => return filter,
+        Scope::OnlyDeleted => {
+            let mut condition = Document::new();
+            condition.insert("$ne", Bson::Null);
+            Bson::Document(condition)
+        }
+    };
+
+    let mut filter = filter.unwrap_or_default();
+    if !constrains(&filter, key) {
+        filter.insert(key, condition);
+    }
+    Some(filter)
+}
+
+/// Whether a filter mentions `key`, at its top level or in one of its logical operators.
+fn constrains(filter: &Document, key: &str) -> bool {
+    filter.iter().any(|(k, v)| match (k.as_str(), v) {
+        ("$and", Bson::Array(conditions))
+        | ("$or", Bson::Array(conditions))
+        | ("$nor", Bson::Array(conditions)) => conditions
+            .iter()
+            .any(|c| matches!(c, Bson::Document(c) if constrains(c, key))),
+        (k, _) => k == key,
+    })
+}
+
+/// Restricts a deletion to the documents in scope which aren't deleted yet, so deleting never
+/// stamps a deleted document again.
+pub(crate) fn deletable<M: BlockingModel>(query: Document, scope: Scope, key: &str) -> Document {
+    let mut query = scoped::<M>(Some(query), scope).unwrap_or_default();
+    if !constrains(&query, key) {
+        query.insert(key, Bson::Null);
+    }
+    if query.get(key) == Some(&Bson::Null) {
+        return query;
+    }
+
+    let mut active = Document::new();
+    active.insert(key, Bson::Null);
+    let mut both = Document::new();
+    both.insert("$and", vec![Bson::Document(query), Bson::Document(active)]);
+    both
+}
+
+/// Filter matching no document.
+fn nothing() -> Document {
+    let mut none = Document::new();
+    none.insert("$in", Bson::Array(Vec::new()));
+    let mut filter = Document::new();
+    filter.insert("_id", none);
+    filter
+}
+
+/// Update stamping the deletion timestamp of a soft deleted model.
+pub(crate) fn deletion(key: &str) -> UpdateModifications {
+    let mut set = Document::new();
+    set.insert(key, Bson::UtcDatetime(Utc::now()));
+    let mut update = Document::new();
+    update.insert("$set", set);
+    UpdateModifications::Document(update)
+}
+
+/// Update clearing the deletion timestamp of a soft deleted model.
+pub(crate) fn restoration(key: &str) -> UpdateModifications {
+    let mut unset = Document::new();
+    unset.insert(key, "");
+    let mut update = Document::new();
+    update.insert("$unset", unset);
+    UpdateModifications::Document(update)
+}
+
+pub(crate) fn find_iter<M: BlockingModel>(
+    filter: Option<Document>,
+    options: Option<FindOptions>,
+    scope: Scope,
+) -> Result<ModelCursor<M>> {
+    let filter = scoped::<M>(filter, scope);
+    Ok(ModelCursor::new(M::collection()?.find(filter, options)?))
+}
+
+pub(crate) fn find_one<M: BlockingModel>(
+    filter: Option<Document>,
+    options: Option<FindOneOptions>,
+    scope: Scope,
+) -> Result<Option<M>> {
+    let filter = scoped::<M>(filter, scope);
+    M::collection()?
+        .find_one(filter, options)?
+        .map(from_document)
+        .transpose()
+}
+
+pub(crate) fn count<M: BlockingModel>(
+    filter: Option<Document>,
+    options: Option<CountOptions>,
+    scope: Scope,
+) -> Result<i64> {
+    let filter = scoped::<M>(filter, scope);
+    Ok(M::collection()?.count_documents(filter, options)?)
+}
+
+/// Updates every matching document in scope.
+pub(crate) fn update_many<M: BlockingModel>(
+    query: Document,
+    update: UpdateModifications,
+    scope: Scope,
+) -> Result<UpdateResult> {
+    let query = scoped::<M>(Some(query), scope).unwrap_or_default();
+    let update = timestamped_update::<M>(update);
+    Ok(M::collection()?.update_many(query, update, None)?)
+}
+
+/// Deletes every matching document, soft deleting them if the model allows it.
+pub(crate) fn delete_many<M: BlockingModel>(query: Document, scope: Scope) -> Result<DeleteResult> {
+    match M::soft_delete() {
+        Some(key) => {
+            let query = deletable::<M>(query, scope, key);
+            let result = update_many::<M>(query, deletion(key), Scope::WithDeleted)?;
+            Ok(DeleteResult {
+                deleted_count: result.modified_count,
+            })
+        }
+        None => {
+            let query = scoped::<M>(Some(query), scope).unwrap_or_default();
+            Ok(M::collection()?.delete_many(query, None)?)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{BlockingHooks, Error};
+    use bson::doc;
+    use mongodb::{Collection, Database};
+    use serde::{Deserialize, Serialize};
+
+    #[derive(Serialize, Deserialize)]
+    struct Post {
+        _id: i32,
+    }
+
+    #[derive(Serialize, Deserialize)]
+    struct Archive {
+        _id: i32,
+    }
+
+    impl BlockingHooks for Post {}
+    impl BlockingHooks for Archive {}
+
+    impl BlockingModel for Post {
+        type Id = i32;
+
+        fn database() -> Result<Database> {
+            Err(Error::NotConnected("default".to_owned()))
+        }
+        fn collection() -> Result<Collection> {
+            Err(Error::NotConnected("default".to_owned()))
+        }
+        fn id(&self) -> i32 {
+            self._id
+        }
+        fn check_relations_sync(&self) -> Result<()> {
+            Ok(())
+        }
+    }
+
+    impl BlockingModel for Archive {
+        type Id = i32;
+
+        fn database() -> Result<Database> {
+            Err(Error::NotConnected("default".to_owned()))
+        }
+        fn collection() -> Result<Collection> {
+            Err(Error::NotConnected("default".to_owned()))
+        }
+        fn id(&self) -> i32 {
+            self._id
+        }
+        fn soft_delete() -> Option<&'static str> {
+            Some("deleted_at")
+        }
+        fn check_relations_sync(&self) -> Result<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn scopes_soft_deleted_models() {
+        let filter = Some(doc! {"title": "hello"});
+        assert_eq!(
+            scoped::<Archive>(filter.clone(), Scope::Active),
+            Some(doc! {"title": "hello", "deleted_at": Bson::Null}),
+        );
+        assert_eq!(
+            scoped::<Archive>(filter.clone(), Scope::WithDeleted),
+            filter
+        );
+        assert_eq!(
+            scoped::<Archive>(filter, Scope::OnlyDeleted),
+            Some(doc! {"title": "hello", "deleted_at": {"$ne": Bson::Null}}),
+        );
+        assert_eq!(
+            scoped::<Archive>(None, Scope::Active),
+            Some(doc! {"deleted_at": Bson::Null}),
+        );
+    }
+
+    #[test]
+    fn keeps_explicit_deletion_conditions() {
+        let filter = Some(doc! {"deleted_at": {"$exists": true}});
+        assert_eq!(scoped::<Archive>(filter.clone(), Scope::Active), filter);
+        assert_eq!(
+            scoped::<Archive>(filter.clone(), Scope::OnlyDeleted),
+            filter
+        );
+    }
+
+    #[test]
+    fn keeps_deletion_conditions_in_logical_operators() {
+        let filter = Some(doc! {"$and": [{"title": "hello"}, {"deleted_at": {"$exists": true}}]});
+        assert_eq!(scoped::<Archive>(filter.clone(), Scope::Active), filter);
+
+        let filter = Some(doc! {"$or": [{"$nor": [{"deleted_at": Bson::Null}]}, {"_id": 1}]});
+        assert_eq!(scoped::<Archive>(filter.clone(), Scope::Active), filter);
+
+        let filter = Some(doc! {"$and": [{"title": "hello"}]});
+        assert_eq!(
+            scoped::<Archive>(filter, Scope::Active),
+            Some(doc! {"$and": [{"title": "hello"}], "deleted_at": Bson::Null}),
+        );
+    }
+
+    #[test]
+    fn never_deletes_deleted_documents_again() {
+        assert_eq!(
+            deletable::<Archive>(doc! {"_id": 1}, Scope::Active, "deleted_at"),
+            doc! {"_id": 1, "deleted_at": Bson::Null},
+        );
+        assert_eq!(
+            deletable::<Archive>(doc! {"_id": 1}, Scope::WithDeleted, "deleted_at"),
+            doc! {"_id": 1, "deleted_at": Bson::Null},
+        );
+        assert_eq!(
+            deletable::<Archive>(doc! {"_id": 1}, Scope::OnlyDeleted, "deleted_at"),
+            doc! {"$and": [
+                {"_id": 1, "deleted_at": {"$ne": Bson::Null}},
+                {"deleted_at": Bson::Null},
+            ]},
+        );
+    }
+
+    #[test]
+    fn matches_nothing_deleted_in_other_models() {
+        let filter = Some(doc! {"title": "hello"});
+        assert_eq!(scoped::<Post>(filter.clone(), Scope::Active), filter);
+        assert_eq!(scoped::<Post>(filter.clone(), Scope::WithDeleted), filter);
+        assert_eq!(scoped::<Post>(None, Scope::Active), None);
+        assert_eq!(
+            scoped::<Post>(filter, Scope::OnlyDeleted),
+            Some(doc! {"_id": {"$in": []}}),
+        );
+    }
+}
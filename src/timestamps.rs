@@ -0,0 +1,180 @@
+use bson::{Bson, Document};
+use chrono::Utc;
+use mongodb::options::UpdateModifications;
+
+/// BSON keys of the timestamps maintained on writes by models annotated with
+/// `#[bongo(timestamps)]`.
+///
+/// Timestamps are only written to the database, the saved struct itself is left untouched.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Timestamps {
+    pub created_at: &'static str,
+    pub updated_at: &'static str,
+}
+
+impl Timestamps {
+    /// Sets both timestamps on a document about to be inserted.
+    pub(crate) fn insert(&self, doc: &mut Document) {
+        let now = now();
+        doc.insert(self.created_at, now.clone());
+        doc.insert(self.updated_at, now);
+    }
+
+    /// Turns a replacement document into a pipeline replacing the stored document, bumping its
+    /// update timestamp. A missing creation timestamp keeps the stored one instead of wiping it,
+    /// or is set to now when the pipeline upserts a new document.
+    pub(crate) fn replace(&self, mut doc: Document) -> UpdateModifications {
+        let now = now();
+        if let Some(Bson::Null) | None = doc.get(self.created_at) {
+            doc.remove(self.created_at);
+        }
+        doc.insert(self.updated_at, now.clone());
+
+        let mut if_null = Document::new();
+        if_null.insert(
+            "$ifNull",
+            vec![Bson::String(format!("${}", self.created_at)), now],
+        );
+        let mut stored = Document::new();
+        stored.insert(self.created_at, if_null);
+        let mut literal = Document::new();
+        literal.insert("$literal", doc);
+        let mut merged = Document::new();
+        merged.insert(
+            "$mergeObjects",
+            vec![Bson::Document(stored), Bson::Document(literal)],
+        );
+        let mut stage = Document::new();
+        stage.insert("$replaceWith", merged);
+        UpdateModifications::Pipeline(vec![stage])
+    }
+
+    /// Adds the update timestamp to the `$set` of an update, or as a last stage of a pipeline.
+    pub(crate) fn update(&self, update: UpdateModifications) -> UpdateModifications {
+        match update {
+            UpdateModifications::Document(mut update) => {
+                match update.get_mut("$set") {
+                    Some(Bson::Document(set)) => {
+                        set.insert(self.updated_at, now());
+                    }
+                    _ => {
+                        let mut set = Document::new();
+                        set.insert(self.updated_at, now());
+                        update.insert("$set", set);
+                    }
+                }
+                UpdateModifications::Document(update)
+            }
+            UpdateModifications::Pipeline(mut pipeline) => {
+                let mut set = Document::new();
+                set.insert(self.updated_at, "$$NOW");
+                let mut stage = Document::new();
+                stage.insert("$set", set);
+                pipeline.push(stage);
+                UpdateModifications::Pipeline(pipeline)
+            }
+        }
+    }
+}
+
+fn now() -> Bson {
+    Bson::UtcDatetime(Utc::now())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bson::doc;
+
+    const TIMESTAMPS: Timestamps = Timestamps {
+        created_at: "created_at",
+        updated_at: "updated_at",
+    };
+
+    #[test]
+    fn bumps_update_timestamp_in_updates() {
+        let update = TIMESTAMPS.update(doc! {"$inc": {"views": 1}}.into());
+        match update {
+            UpdateModifications::Document(d) => {
+                assert!(d.get_document("$inc").is_ok());
+                assert!(d
+                    .get_document("$set")
+                    .unwrap()
+                    .get_utc_datetime("updated_at")
+                    .is_ok());
+            }
+            _ => panic!("expected an update document"),
+        }
+
+        let update = TIMESTAMPS.update(doc! {"$set": {"body": "hi"}}.into());
+        match update {
+            UpdateModifications::Document(d) => {
+                let set = d.get_document("$set").unwrap();
+                assert_eq!(set.get_str("body"), Ok("hi"));
+                assert!(set.get_utc_datetime("updated_at").is_ok());
+            }
+            _ => panic!("expected an update document"),
+        }
+    }
+
+    #[test]
+    fn bumps_update_timestamp_in_pipelines() {
+        let stage = doc! {"$set": {"views": {"$add": ["$views", 1]}}};
+        let update = TIMESTAMPS.update(UpdateModifications::Pipeline(vec![stage.clone()]));
+        match update {
+            UpdateModifications::Pipeline(p) => {
+                assert_eq!(p, vec![stage, doc! {"$set": {"updated_at": "$$NOW"}}]);
+            }
+            _ => panic!("expected a pipeline"),
+        }
+    }
+
+    #[test]
+    fn keeps_stored_creation_timestamp_on_replace() {
+        let replacement = match TIMESTAMPS.replace(doc! {"_id": 1, "created_at": Bson::Null}) {
+            UpdateModifications::Pipeline(mut p) => p.remove(0),
+            _ => panic!("expected a pipeline"),
+        };
+        let merged = replacement
+            .get_document("$replaceWith")
+            .unwrap()
+            .get_array("$mergeObjects")
+            .unwrap();
+        let doc = match &merged[1] {
+            Bson::Document(d) => d.get_document("$literal").unwrap(),
+            _ => panic!("expected a document"),
+        };
+        assert!(!doc.contains_key("created_at"));
+        let now = doc.get("updated_at").unwrap();
+        assert!(matches!(now, Bson::UtcDatetime(_)));
+
+        // Upserted documents have no stored creation timestamp and get the update one.
+        assert_eq!(
+            merged[0],
+            Bson::Document(doc! {"created_at": {"$ifNull": ["$created_at", now.clone()]}})
+        );
+    }
+
+    #[test]
+    fn overrides_creation_timestamp_on_replace_when_set() {
+        let created_at = Utc::now();
+        let replacement = match TIMESTAMPS.replace(doc! {"_id": 1, "created_at": created_at}) {
+            UpdateModifications::Pipeline(mut p) => p.remove(0),
+            _ => panic!("expected a pipeline"),
+        };
+        let merged = replacement
+            .get_document("$replaceWith")
+            .unwrap()
+            .get_array("$mergeObjects")
+            .unwrap();
+        match &merged[1] {
+            Bson::Document(d) => assert_eq!(
+                d.get_document("$literal")
+                    .unwrap()
+                    .get_utc_datetime("created_at"),
+                Ok(&created_at),
+            ),
+            _ => panic!("expected a document"),
+        }
+    }
+}
@@ -0,0 +1,208 @@
+use crate::{
+    filter::{value_to_bson, values_to_bson},
+    Result,
+};
+use bson::{doc, Bson, Document, EncoderError, EncoderResult};
+use mongodb::options::UpdateModifications;
+use serde::Serialize;
+use std::marker::PhantomData;
+
+/// Update operators on the documents of a model, built from the `update` function generated by
+/// the derives.
+///
+/// Taken by the updating methods of `BlockingModel` and `Model`. A value which can't be
+/// represented in BSON makes the update fail with `Error::BsonEncode` when the operation runs.
+pub struct Update<M> {
+    document: EncoderResult<Document>,
+    model: PhantomData<fn() -> M>,
+}
+
+impl<M> Update<M> {
+    pub fn new() -> Self {
+        Self {
+            document: Ok(Document::new()),
+            model: PhantomData,
+        }
+    }
+
+    /// Adds `key: value` to the `operator` document, creating it if needed.
+    pub fn with(mut self, operator: &str, key: &str, value: Bson) -> Self {
+        if let Ok(document) = &mut self.document {
+            match document.get_mut(operator) {
+                Some(Bson::Document(d)) => {
+                    d.insert(key, value);
+                }
+                _ => {
+                    document.insert(operator, doc! {key: value});
+                }
+            }
+        }
+        self
+    }
+
+    /// Keeps the first encoding error, reported once the update is used.
+    fn fail(mut self, error: EncoderError) -> Self {
+        if self.document.is_ok() {
+            self.document = Err(error);
+        }
+        self
+    }
+
+    pub fn is_empty(&self) -> bool {
+        matches!(&self.document, Ok(d) if d.is_empty())
+    }
+    pub fn into_document(self) -> Result<Document> {
+        Ok(self.document?)
+    }
+}
+
+impl<M> Default for Update<M> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Updates of the documents of the model `M` taken by the updating methods: update documents,
+/// aggregation pipelines and builders.
+///
+/// Raw documents and pipelines are accepted for any model, builders only for their own.
+pub trait IntoUpdate<M> {
+    fn into_update(self) -> Result<UpdateModifications>;
+}
+
+impl<M> IntoUpdate<M> for UpdateModifications {
+    fn into_update(self) -> Result<UpdateModifications> {
+        Ok(self)
+    }
+}
+impl<M> IntoUpdate<M> for Document {
+    fn into_update(self) -> Result<UpdateModifications> {
+        Ok(UpdateModifications::Document(self))
+    }
+}
+impl<M> IntoUpdate<M> for Vec<Document> {
+    fn into_update(self) -> Result<UpdateModifications> {
+        Ok(UpdateModifications::Pipeline(self))
+    }
+}
+impl<M> IntoUpdate<M> for Update<M> {
+    fn into_update(self) -> Result<UpdateModifications> {
+        self.into_document().map(UpdateModifications::Document)
+    }
+}
+
+/// Numeric field types accepted by `inc` and `mul`.
+pub trait Numeric: Serialize {}
+
+impl Numeric for i32 {}
+impl Numeric for i64 {}
+impl Numeric for f32 {}
+impl Numeric for f64 {}
+impl<T: Numeric> Numeric for Option<T> {}
+
+/// Update operators on a single field of type `T` of the model `M`.
+///
+/// Every operator hands back the builder `B`, so operators on several fields can be chained.
+pub struct FieldUpdate<M, T, B> {
+    update: Update<M>,
+    key: &'static str,
+    types: PhantomData<fn() -> (T, B)>,
+}
+
+impl<M, T, B> FieldUpdate<M, T, B>
+where
+    T: Serialize,
+    B: From<Update<M>>,
+{
+    #[doc(hidden)]
+    pub fn new(update: Update<M>, key: &'static str) -> Self {
+        Self {
+            update,
+            key,
+            types: PhantomData,
+        }
+    }
+
+    fn op(self, operator: &str, value: EncoderResult<Bson>) -> B {
+        match value {
+            Ok(v) => self.update.with(operator, self.key, v),
+            Err(e) => self.update.fail(e),
+        }
+        .into()
+    }
+
+    pub fn set<V: Into<T>>(self, value: V) -> B {
+        self.op("$set", value_to_bson(&value.into()))
+    }
+    pub fn unset(self) -> B {
+        self.op("$unset", Ok(Bson::String(String::new())))
+    }
+    pub fn min<V: Into<T>>(self, value: V) -> B {
+        self.op("$min", value_to_bson(&value.into()))
+    }
+    pub fn max<V: Into<T>>(self, value: V) -> B {
+        self.op("$max", value_to_bson(&value.into()))
+    }
+    /// Renames the field in the stored documents, which will then no longer match the model.
+    pub fn rename(self, name: &str) -> B {
+        self.op("$rename", Ok(Bson::String(name.to_owned())))
+    }
+    /// Sets the field to the current date on the server.
+    pub fn current_date(self) -> B {
+        self.op("$currentDate", Ok(Bson::Boolean(true)))
+    }
+}
+
+impl<M, T, B> FieldUpdate<M, T, B>
+where
+    T: Numeric,
+    B: From<Update<M>>,
+{
+    pub fn inc<V: Into<T>>(self, value: V) -> B {
+        self.op("$inc", value_to_bson(&value.into()))
+    }
+    #[allow(clippy::should_implement_trait)]
+    pub fn mul<V: Into<T>>(self, value: V) -> B {
+        self.op("$mul", value_to_bson(&value.into()))
+    }
+}
+
+impl<M, T, B> FieldUpdate<M, Vec<T>, B>
+where
+    T: Serialize,
+    B: From<Update<M>>,
+{
+    pub fn push<V: Into<T>>(self, value: V) -> B {
+        self.op("$push", value_to_bson(&value.into()))
+    }
+    pub fn push_all<I, V>(self, values: I) -> B
+    where
+        I: IntoIterator<Item = V>,
+        V: Into<T>,
+    {
+        self.op("$push", each(values))
+    }
+    /// Removes every element equal to `value`.
+    pub fn pull<V: Into<T>>(self, value: V) -> B {
+        self.op("$pull", value_to_bson(&value.into()))
+    }
+    pub fn add_to_set<V: Into<T>>(self, value: V) -> B {
+        self.op("$addToSet", value_to_bson(&value.into()))
+    }
+    pub fn add_all_to_set<I, V>(self, values: I) -> B
+    where
+        I: IntoIterator<Item = V>,
+        V: Into<T>,
+    {
+        self.op("$addToSet", each(values))
+    }
+}
+
+fn each<I, V, T>(values: I) -> EncoderResult<Bson>
+where
+    I: IntoIterator<Item = V>,
+    V: Into<T>,
+    T: Serialize,
+{
+    values_to_bson(values).map(|values| Bson::Document(doc! {"$each": values}))
+}
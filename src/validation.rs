@@ -0,0 +1,149 @@
+use regex::Regex;
+use std::fmt::{self, Display};
+
+/// A single failed validation rule.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ValidationError {
+    /// BSON key of the failing field, or empty for rules on the whole model.
+    pub field: String,
+    pub message: String,
+}
+
+/// Every rule which failed while validating a model.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ValidationErrors {
+    pub errors: Vec<ValidationError>,
+}
+
+impl ValidationErrors {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn add<F: Into<String>, M: Into<String>>(&mut self, field: F, message: M) {
+        self.errors.push(ValidationError {
+            field: field.into(),
+            message: message.into(),
+        });
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.errors.is_empty()
+    }
+
+    /// Turns the collected errors into a result, failing if there is at least one.
+    pub fn into_result(self) -> crate::Result<()> {
+        if self.is_empty() {
+            Ok(())
+        } else {
+            Err(crate::Error::Validation(self))
+        }
+    }
+}
+
+impl Display for ValidationErrors {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, e) in self.errors.iter().enumerate() {
+            if i > 0 {
+                write!(f, ", ")?;
+            }
+            if e.field.is_empty() {
+                write!(f, "{}", e.message)?;
+            } else {
+                write!(f, "{} {}", e.field, e.message)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Values with a length checked by `min_len` and `max_len`, in characters for strings.
+pub trait Length {
+    fn length(&self) -> usize;
+}
+
+impl Length for String {
+    fn length(&self) -> usize {
+        self.chars().count()
+    }
+}
+impl Length for str {
+    fn length(&self) -> usize {
+        self.chars().count()
+    }
+}
+impl<T> Length for Vec<T> {
+    fn length(&self) -> usize {
+        self.len()
+    }
+}
+
+// Helpers called by the `validate` method generated by the derives.
+
+#[doc(hidden)]
+pub fn check_min_len<T>(errors: &mut ValidationErrors, field: &str, value: &T, min: usize)
+where
+    T: Length + ?Sized,
+{
+    if value.length() < min {
+        errors.add(field, format!("must have a length of at least {}", min));
+    }
+}
+
+#[doc(hidden)]
+pub fn check_max_len<T>(errors: &mut ValidationErrors, field: &str, value: &T, max: usize)
+where
+    T: Length + ?Sized,
+{
+    if value.length() > max {
+        errors.add(field, format!("must have a length of at most {}", max));
+    }
+}
+
+#[doc(hidden)]
+pub fn check_range<T>(
+    errors: &mut ValidationErrors,
+    field: &str,
+    value: &T,
+    min: Option<T>,
+    max: Option<T>,
+) where
+    T: PartialOrd + Display,
+{
+    match (min, max) {
+        (Some(min), Some(max)) if *value < min || *value > max => {
+            errors.add(field, format!("must be between {} and {}", min, max))
+        }
+        (Some(min), None) if *value < min => errors.add(field, format!("must be at least {}", min)),
+        (None, Some(max)) if *value > max => errors.add(field, format!("must be at most {}", max)),
+        _ => {}
+    }
+}
+
+#[doc(hidden)]
+pub fn check_regex(errors: &mut ValidationErrors, field: &str, value: &str, regex: &Regex) {
+    if !regex.is_match(value) {
+        errors.add(field, format!("must match {}", regex.as_str()));
+    }
+}
+
+#[doc(hidden)]
+pub fn check_email(errors: &mut ValidationErrors, field: &str, value: &str) {
+    if !is_email(value) {
+        errors.add(field, "must be a valid email address");
+    }
+}
+
+/// Loose email check: a single `@` between a non-empty local part and a dotted domain, without
+/// whitespace.
+fn is_email(value: &str) -> bool {
+    let mut parts = value.split('@');
+    let (local, domain) = match (parts.next(), parts.next(), parts.next()) {
+        (Some(l), Some(d), None) => (l, d),
+        _ => return false,
+    };
+    !local.is_empty()
+        && !value.chars().any(char::is_whitespace)
+        && domain.split('.').count() > 1
+        && domain.split('.').all(|p| !p.is_empty())
+}
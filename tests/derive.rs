@@ -1,8 +1,13 @@
-use bongo::{BlockingModel, Model};
-use bson::oid::ObjectId;
+#![allow(dead_code)]
+
+use bongo::{BlockingModel, Model, Schema};
+use bson::{doc, oid::ObjectId};
+use futures::StreamExt;
+use mongodb::options::{FindOptions, Hint};
 use serde::{Deserialize, Serialize};
 
 #[derive(Model, Serialize, Deserialize)]
+#[bongo(referenced_by(Todo, "author"), referenced_by(Category, "curator"))]
 struct User {
     #[serde(rename = "_id")]
     id: ObjectId,
@@ -15,17 +20,103 @@ struct Todo {
     _id: i32,
     #[bongo(has_one(User))]
     author: ObjectId,
+    #[bongo(has_one(User))]
+    reviewer: Option<ObjectId>,
+    #[bongo(has_many(User))]
+    watchers: Option<Vec<ObjectId>>,
+}
+
+#[derive(Model, Serialize, Deserialize)]
+struct Category {
+    _id: ObjectId,
+    #[bongo(has_one(User))]
+    curator: ObjectId,
 }
 
 #[derive(Model, Serialize, Deserialize)]
 struct Useless {
     #[serde(rename = "_id")]
     id: f64,
-    #[bongo(has_many(BlockingUseless, "stuff", "stuff_async"))]
+    #[bongo(has_many(Stuff, "stuff", "stuff_async"))]
     stuff: Vec<String>,
 }
 
+#[derive(Model, Serialize, Deserialize)]
+struct Stuff {
+    _id: String,
+}
+
 #[derive(BlockingModel, Serialize, Deserialize)]
+#[bongo(referenced_by(Useless, "stuff", "owners_sync", "owners"))]
 struct BlockingUseless {
     _id: String,
 }
+
+#[derive(Model, Serialize, Deserialize)]
+#[bongo(connection = "analytics", collection = "events")]
+struct Event {
+    _id: ObjectId,
+    name: String,
+}
+
+fn authored_count_sync(user: &User) -> bongo::Result<usize> {
+    Ok(user.todos_sync()?.len())
+}
+
+async fn authored_count(user: &User) -> bongo::Result<usize> {
+    Ok(user.todos().await?.len())
+}
+
+async fn curated_count(user: &User) -> bongo::Result<usize> {
+    Ok(user.categories().await?.len() + user.categories_sync()?.len())
+}
+
+fn owner_count_sync(useless: &BlockingUseless) -> bongo::Result<usize> {
+    Ok(useless.owners_sync()?.len())
+}
+
+fn reviewer_name_sync(todo: &Todo) -> bongo::Result<Option<String>> {
+    Ok(todo.reviewer_sync()?.map(|u| u.username))
+}
+
+async fn watcher_count(todo: &Todo) -> bongo::Result<usize> {
+    Ok(todo.watchers().await?.map_or(0, |w| w.len()))
+}
+
+fn user_names_sync() -> bongo::Result<Vec<String>> {
+    User::find_iter_sync(None)?
+        .map(|u| u.map(|u| u.username))
+        .collect()
+}
+
+async fn user_names() -> bongo::Result<Vec<String>> {
+    let mut users = User::find_stream(None).await?;
+    let mut names = Vec::new();
+    while let Some(user) = users.next().await {
+        names.push(user?.username);
+    }
+    Ok(names)
+}
+
+fn user_page_sync(page: i64) -> bongo::Result<Vec<User>> {
+    User::find_with_options_sync(
+        None,
+        FindOptions {
+            sort: Some(doc! {"username": 1}),
+            projection: Some(doc! {"username": 1, "password": 1}),
+            skip: Some(page * 50),
+            limit: Some(50),
+            batch_size: Some(50),
+            hint: Some(Hint::Name("username_1".to_owned())),
+            ..Default::default()
+        },
+    )
+}
+
+async fn rename_user(old: &str, new: User) -> bongo::Result<Option<User>> {
+    User::insert_one(&new).await?;
+    User::delete_one(User::filter().username().eq(new.username.clone())).await?;
+    User::replace_one(User::filter().username().eq(old), &new).await?;
+    User::find_one_and_replace(User::filter().username().eq(old), &new).await?;
+    User::find_one_and_delete(User::filter().username().eq(new.username)).await
+}
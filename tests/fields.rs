@@ -0,0 +1,71 @@
+#![allow(dead_code)]
+
+use bongo::{BlockingModel, Schema};
+use bson::oid::ObjectId;
+use serde::{Deserialize, Serialize};
+
+#[derive(BlockingModel, Serialize, Deserialize)]
+struct Account {
+    #[serde(rename = "_id")]
+    id: ObjectId,
+    #[serde(rename = "login")]
+    username: String,
+    display_name: String,
+    #[serde(skip)]
+    session: Option<String>,
+}
+
+#[derive(BlockingModel, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Profile {
+    #[serde(rename = "_id")]
+    id: ObjectId,
+    avatar_url: String,
+    #[serde(rename(serialize = "bio", deserialize = "biography"))]
+    about_me: String,
+    r#type: String,
+}
+
+// Builders are only reachable through `Schema`, so the model can declare items with their names.
+#[derive(BlockingModel, Serialize, Deserialize)]
+struct Order {
+    _id: ObjectId,
+    total: i64,
+}
+
+struct OrderUpdate {
+    total: i64,
+}
+
+impl Order {
+    fn update(&mut self, update: OrderUpdate) {
+        self.total = update.total;
+    }
+}
+
+#[test]
+fn honors_renames() {
+    assert_eq!(<Account as Schema>::Fields::ID, "_id");
+    assert_eq!(<Account as Schema>::Fields::USERNAME, "login");
+    assert_eq!(<Account as Schema>::Fields::DISPLAY_NAME, "display_name");
+}
+
+#[test]
+fn honors_rename_all() {
+    assert_eq!(<Profile as Schema>::Fields::ID, "_id");
+    assert_eq!(<Profile as Schema>::Fields::AVATAR_URL, "avatarUrl");
+    assert_eq!(<Profile as Schema>::Fields::ABOUT_ME, "bio");
+    assert_eq!(<Profile as Schema>::Fields::TYPE, "type");
+}
+
+#[test]
+fn keeps_model_items_with_builder_names() {
+    let mut order = Order {
+        _id: ObjectId::new().unwrap(),
+        total: 1,
+    };
+    order.update(OrderUpdate { total: 2 });
+    assert_eq!(order.total, 2);
+    assert_eq!(<Order as Schema>::Fields::TOTAL, "total");
+    assert!(!bongo::Update::from(<Order as Schema>::update().total().set(3)).is_empty());
+}
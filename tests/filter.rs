@@ -0,0 +1,88 @@
+#![allow(dead_code)]
+
+use bongo::{BlockingModel, Error, Schema};
+use bson::{doc, oid::ObjectId, Document};
+use serde::{Deserialize, Serialize};
+
+#[derive(BlockingModel, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Member {
+    #[serde(rename = "_id")]
+    id: ObjectId,
+    user_name: String,
+    age: i32,
+    tags: Vec<String>,
+    karma: u64,
+}
+
+#[test]
+fn compiles_field_conditions() {
+    let filter: Document = Member::filter()
+        .user_name()
+        .eq("bob")
+        .into_document()
+        .unwrap();
+    assert_eq!(filter, doc! {"userName": "bob"});
+
+    let filter: Document = Member::filter().age().gte(18).into_document().unwrap();
+    assert_eq!(filter, doc! {"age": {"$gte": 18}});
+
+    let filter: Document = Member::filter()
+        .age()
+        .is_in(vec![1, 2])
+        .into_document()
+        .unwrap();
+    assert_eq!(filter, doc! {"age": {"$in": [1, 2]}});
+
+    let filter: Document = Member::filter()
+        .tags()
+        .contains("rust")
+        .into_document()
+        .unwrap();
+    assert_eq!(filter, doc! {"tags": "rust"});
+}
+
+#[test]
+fn combines_filters() {
+    let filter: Document = Member::filter()
+        .user_name()
+        .eq("bob")
+        .and(Member::filter().age().gt(18))
+        .and(Member::filter().age().lt(65))
+        .into_document()
+        .unwrap();
+    assert_eq!(
+        filter,
+        doc! {"$and": [{"userName": "bob"}, {"age": {"$gt": 18}}, {"age": {"$lt": 65}}]}
+    );
+
+    let filter: Document = (!Member::filter().age().lt(18))
+        .or(Member::filter().tags().size(0))
+        .into_document()
+        .unwrap();
+    assert_eq!(
+        filter,
+        doc! {"$or": [{"$nor": [{"age": {"$lt": 18}}]}, {"tags": {"$size": 0i64}}]}
+    );
+}
+
+#[test]
+fn reports_unencodable_values() {
+    let filter = Member::filter()
+        .user_name()
+        .eq("bob")
+        .and(Member::filter().karma().gt(1u64));
+    assert!(matches!(filter.into_document(), Err(Error::BsonEncode(_))));
+
+    let query = Member::query().filter(Member::filter().karma().lt(1u64));
+    assert!(matches!(query.count_sync(), Err(Error::BsonEncode(_))));
+}
+
+fn adults_sync() -> bongo::Result<Vec<Member>> {
+    Member::find_sync(Member::filter().age().gte(18), None, None)
+}
+
+fn purge_minors_sync() -> bongo::Result<i64> {
+    Member::delete_many_sync(Member::filter().age().lt(18))?;
+    Member::count_documents_sync(Member::filter().age().lt(18))
+}
@@ -0,0 +1,63 @@
+use bongo::{BlockingModel, Error};
+use bson::oid::ObjectId;
+use mongodb::options::ClientOptions;
+use serde::{Deserialize, Serialize};
+
+#[derive(BlockingModel, Serialize, Deserialize)]
+#[bongo(connection = "globals")]
+struct Widget {
+    _id: ObjectId,
+}
+
+#[derive(BlockingModel, Serialize, Deserialize)]
+#[bongo(connection = "cache")]
+struct Gadget {
+    _id: ObjectId,
+}
+
+#[test]
+fn connection_lifecycle() {
+    let uri = "mongodb://localhost:27017";
+
+    assert!(matches!(Widget::collection(), Err(Error::NotConnected(_))));
+
+    bongo::connect_named("globals", uri, "bongo_globals").unwrap();
+    assert!(matches!(
+        bongo::connect_named("globals", uri, "bongo_globals"),
+        Err(Error::AlreadyConnected(_))
+    ));
+    assert_eq!(Widget::collection().unwrap().name(), "widgets");
+
+    bongo::reconnect_named("globals", ClientOptions::parse(uri).unwrap()).unwrap();
+    assert_eq!(
+        bongo::database_named("globals").unwrap().name(),
+        "bongo_globals"
+    );
+    assert_eq!(Widget::collection().unwrap().name(), "widgets");
+
+    bongo::disconnect_named("globals").unwrap();
+    assert!(matches!(Widget::collection(), Err(Error::NotConnected(_))));
+    assert!(matches!(
+        bongo::disconnect_named("globals"),
+        Err(Error::NotConnected(_))
+    ));
+}
+
+#[test]
+fn connection_changes_rebuild_cached_collections() {
+    let uri = "mongodb://localhost:27017";
+
+    bongo::connect_named("cache", uri, "bongo_first").unwrap();
+    let collection = Gadget::collection().unwrap();
+    assert_eq!(collection.namespace().db, "bongo_first");
+    assert!(collection.write_concern().is_none());
+
+    let options = ClientOptions::parse(&format!("{}/?w=majority", uri)).unwrap();
+    bongo::reconnect_named("cache", options).unwrap();
+    assert!(Gadget::collection().unwrap().write_concern().is_some());
+
+    bongo::disconnect_named("cache").unwrap();
+    bongo::connect_named("cache", uri, "bongo_second").unwrap();
+    assert_eq!(Gadget::collection().unwrap().namespace().db, "bongo_second");
+    bongo::disconnect_named("cache").unwrap();
+}
@@ -0,0 +1,80 @@
+use bongo::{BlockingHooks, BlockingModel, Error, Hooks, Model};
+use bson::oid::ObjectId;
+use serde::{Deserialize, Serialize};
+
+#[derive(Model, Serialize, Deserialize)]
+#[bongo(hooks)]
+struct Article {
+    _id: ObjectId,
+    title: String,
+    published: bool,
+}
+
+impl BlockingHooks for Article {
+    fn before_save_sync(&self) -> bongo::Result<()> {
+        if self.title.is_empty() {
+            return Err(Error::Aborted("title is empty".to_owned()));
+        }
+        Ok(())
+    }
+    fn before_remove_sync(&self) -> bongo::Result<()> {
+        if self.published {
+            return Err(Error::Aborted("published articles are kept".to_owned()));
+        }
+        Ok(())
+    }
+}
+
+#[bongo::re_exports::async_trait::async_trait]
+impl Hooks for Article {
+    async fn before_save(&self) -> bongo::Result<()> {
+        if self.title.len() > 10 {
+            return Err(Error::Aborted("title is too long".to_owned()));
+        }
+        Ok(())
+    }
+}
+
+fn article(title: &str, published: bool) -> Article {
+    Article {
+        _id: ObjectId::new().unwrap(),
+        title: title.to_owned(),
+        published,
+    }
+}
+
+fn message<T>(result: bongo::Result<T>) -> String {
+    match result {
+        Err(Error::Aborted(m)) => m,
+        Err(e) => panic!("unexpected error {}", e),
+        Ok(_) => panic!("expected an error"),
+    }
+}
+
+// Hooks run before the collection is resolved, so aborting never needs a connection.
+#[test]
+fn before_hooks_abort_blocking_operations() {
+    assert_eq!(message(article("", false).save_sync()), "title is empty");
+    assert_eq!(
+        message(article("News", true).remove_sync()),
+        "published articles are kept"
+    );
+}
+
+#[test]
+fn async_operations_run_both_hook_forms() {
+    use futures::executor::block_on;
+
+    assert_eq!(
+        message(block_on(article("", false).save())),
+        "title is empty"
+    );
+    assert_eq!(
+        message(block_on(article("Breaking news", false).save())),
+        "title is too long"
+    );
+    assert_eq!(
+        message(block_on(article("News", true).remove())),
+        "published articles are kept"
+    );
+}
@@ -0,0 +1,124 @@
+#![allow(dead_code)]
+
+use bongo::BlockingModel;
+use bson::{doc, oid::ObjectId, Document};
+use serde::{Deserialize, Serialize};
+
+#[derive(BlockingModel, Serialize, Deserialize)]
+#[bongo(index(fields("last_name", "-age"), sparse, name = "by_name_and_age"))]
+#[bongo(index(fields("address.city"), unique))]
+struct Person {
+    _id: ObjectId,
+    #[bongo(unique)]
+    #[serde(rename = "mail")]
+    email: String,
+    #[bongo(index)]
+    last_name: String,
+    age: i32,
+    address: Document,
+}
+
+#[derive(BlockingModel, Serialize, Deserialize)]
+#[bongo(index(
+    fields("user", "-created_at"),
+    partial_filter = "active_only",
+    collation(locale = "fr", strength = 2, numeric_ordering = true)
+))]
+struct Session {
+    _id: ObjectId,
+    user: ObjectId,
+    #[bongo(ttl = "1d12h")]
+    created_at: bson::UtcDateTime,
+    #[bongo(index(unique, partial_filter = "active_only"))]
+    token: String,
+    active: bool,
+}
+
+fn active_only() -> Document {
+    doc! {"active": true}
+}
+
+#[derive(BlockingModel, Serialize, Deserialize)]
+struct Invite {
+    _id: ObjectId,
+    #[bongo(unique, ttl = "1d")]
+    code: String,
+    #[bongo(index)]
+    #[bongo(unique)]
+    email: String,
+}
+
+#[derive(BlockingModel, Serialize, Deserialize)]
+struct Plain {
+    _id: ObjectId,
+}
+
+#[test]
+fn declares_indexes() {
+    let indexes: Vec<_> = Person::indexes()
+        .into_iter()
+        .map(|i| (i.keys, i.options.unwrap()))
+        .collect();
+
+    assert_eq!(
+        indexes,
+        vec![
+            (doc! {"mail": 1}, doc! {"unique": true}),
+            (doc! {"last_name": 1}, doc! {}),
+            (
+                doc! {"last_name": 1, "age": -1},
+                doc! {"name": "by_name_and_age", "sparse": true}
+            ),
+            (doc! {"address.city": 1}, doc! {"unique": true}),
+        ]
+    );
+    assert!(Plain::indexes().is_empty());
+}
+
+#[test]
+fn declares_ttl_and_partial_indexes() {
+    let indexes: Vec<_> = Session::indexes()
+        .into_iter()
+        .map(|i| (i.keys, i.options.unwrap()))
+        .collect();
+
+    assert_eq!(
+        indexes,
+        vec![
+            (
+                doc! {"created_at": 1},
+                doc! {"expireAfterSeconds": 129_600i64}
+            ),
+            (
+                doc! {"token": 1},
+                doc! {"unique": true, "partialFilterExpression": {"active": true}}
+            ),
+            (
+                doc! {"user": 1, "created_at": -1},
+                doc! {
+                    "partialFilterExpression": {"active": true},
+                    "collation": {"locale": "fr", "strength": 2, "numericOrdering": true},
+                }
+            ),
+        ]
+    );
+}
+
+#[test]
+fn merges_field_flags_into_one_index() {
+    let indexes: Vec<_> = Invite::indexes()
+        .into_iter()
+        .map(|i| (i.keys, i.options.unwrap()))
+        .collect();
+
+    assert_eq!(
+        indexes,
+        vec![
+            (
+                doc! {"code": 1},
+                doc! {"unique": true, "expireAfterSeconds": 86_400i64}
+            ),
+            (doc! {"email": 1}, doc! {"unique": true}),
+        ]
+    );
+}
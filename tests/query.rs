@@ -0,0 +1,57 @@
+use bongo::{BlockingModel, Schema};
+use bson::{doc, oid::ObjectId};
+use serde::{Deserialize, Serialize};
+
+#[derive(BlockingModel, Serialize, Deserialize)]
+struct Post {
+    _id: ObjectId,
+    title: String,
+    views: i64,
+}
+
+#[test]
+fn merges_filters_and_sorts() {
+    let query = Post::query()
+        .filter(doc! {"title": "hello", "views": 1})
+        .filter(doc! {"views": {"$gt": 10}})
+        .sort(doc! {"views": -1})
+        .sort(doc! {"title": 1});
+
+    assert_eq!(
+        query.filter_document(),
+        &doc! {"title": "hello", "$and": [{"views": 1}, {"views": {"$gt": 10}}]}
+    );
+    assert_eq!(query.options().sort, Some(doc! {"views": -1, "title": 1}));
+}
+
+#[test]
+fn combines_repeated_conditions() {
+    let query = Post::query()
+        .filter(Post::filter().views().gt(18))
+        .filter(Post::filter().views().lt(65))
+        .filter(doc! {"$and": [{"title": "hello"}]});
+
+    assert_eq!(
+        query.filter_document(),
+        &doc! {"$and": [
+            {"views": {"$gt": 18i64}},
+            {"views": {"$lt": 65i64}},
+            {"title": "hello"},
+        ]}
+    );
+}
+
+#[test]
+fn forwards_options() {
+    let query = Post::query()
+        .project(doc! {"title": 1})
+        .skip(20)
+        .limit(10)
+        .batch_size(5);
+
+    let options = query.options();
+    assert_eq!(options.projection, Some(doc! {"title": 1}));
+    assert_eq!(options.skip, Some(20));
+    assert_eq!(options.limit, Some(10));
+    assert_eq!(options.batch_size, Some(5));
+}
@@ -0,0 +1,64 @@
+#![allow(dead_code)]
+
+use bongo::BlockingModel;
+use bson::oid::ObjectId;
+use serde::{Deserialize, Serialize};
+
+#[derive(BlockingModel, Serialize, Deserialize)]
+struct Ticket {
+    _id: ObjectId,
+    #[bongo(index)]
+    number: i64,
+}
+
+#[derive(BlockingModel, Serialize, Deserialize)]
+#[bongo(connection = "archive", collection = "old_invoices")]
+struct Invoice {
+    _id: ObjectId,
+}
+
+mod legacy {
+    use bongo::BlockingModel;
+    use bson::oid::ObjectId;
+    use serde::{Deserialize, Serialize};
+
+    #[derive(BlockingModel, Serialize, Deserialize)]
+    #[bongo(connection = "archive")]
+    pub struct Ticket {
+        _id: ObjectId,
+    }
+}
+
+#[test]
+fn registers_models_before_main() {
+    let models = bongo::registered_models();
+    let names: Vec<_> = models.iter().map(|m| m.type_name).collect();
+    assert_eq!(
+        names,
+        vec![
+            "registry::Invoice",
+            "registry::Ticket",
+            "registry::legacy::Ticket"
+        ]
+    );
+
+    assert_eq!(models[0].connection, "archive");
+    assert_eq!(models[0].collection, "old_invoices");
+    assert_eq!((models[1].indexes)().len(), 1);
+    assert_eq!(models[2].connection, "archive");
+}
+
+#[test]
+fn reports_every_model_when_syncing_indexes() {
+    let reports = bongo::sync_all_indexes(false);
+    let names: Vec<_> = reports.iter().map(|(m, _)| m.type_name).collect();
+    assert_eq!(
+        names,
+        vec![
+            "registry::Invoice",
+            "registry::Ticket",
+            "registry::legacy::Ticket"
+        ]
+    );
+    assert!(reports.iter().all(|(_, r)| r.is_err()));
+}
@@ -0,0 +1,108 @@
+#![allow(dead_code)]
+
+use bongo::{BlockingModel, Model, Schema};
+use bson::oid::ObjectId;
+use futures::executor::block_on;
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Model, Serialize, Deserialize)]
+struct Person {
+    _id: ObjectId,
+    name: String,
+}
+
+#[derive(Model, Serialize, Deserialize)]
+struct Task {
+    _id: ObjectId,
+    #[bongo(has_one(Person))]
+    owner: ObjectId,
+    #[bongo(has_one(Person))]
+    reviewer: Option<ObjectId>,
+    #[bongo(has_many(Person))]
+    watchers: Vec<ObjectId>,
+}
+
+#[derive(Model, Serialize, Deserialize)]
+struct Review {
+    _id: ObjectId,
+    #[bongo(has_one(Person))]
+    reviewer: Option<ObjectId>,
+    #[bongo(has_many(Person))]
+    watchers: Option<Vec<ObjectId>>,
+}
+
+fn task(reviewer: Option<ObjectId>, watchers: Vec<ObjectId>) -> Task {
+    Task {
+        _id: ObjectId::new().unwrap(),
+        owner: ObjectId::new().unwrap(),
+        reviewer,
+        watchers,
+    }
+}
+
+fn tasks_with_owners_sync() -> bongo::Result<Vec<(Task, Person)>> {
+    Task::relations()
+        .owner()
+        .populate_sync(Task::find_sync(None, None, None)?)
+}
+
+async fn tasks_with_watchers() -> bongo::Result<Vec<(Task, Vec<Person>)>> {
+    Task::relations()
+        .watchers()
+        .populate(Task::find(None, None, None).await?)
+        .await
+}
+
+// Without any referenced id there is nothing to load, so no connection is needed.
+#[test]
+fn populates_empty_references_without_querying() {
+    let populated = Task::relations()
+        .reviewer()
+        .populate_sync(vec![task(None, vec![]), task(None, vec![])])
+        .unwrap();
+    assert_eq!(populated.len(), 2);
+    assert!(populated.iter().all(|(_, reviewer)| reviewer.is_none()));
+
+    let populated = Task::relations()
+        .watchers()
+        .populate_sync(vec![task(None, vec![])])
+        .unwrap();
+    assert!(populated[0].1.is_empty());
+}
+
+// Unset optional references have nothing to check, so no connection is needed either.
+#[test]
+fn checks_unset_references_without_querying() {
+    let review = Review {
+        _id: ObjectId::new().unwrap(),
+        reviewer: None,
+        watchers: None,
+    };
+    review.check_relations_sync().unwrap();
+    block_on(review.check_relations()).unwrap();
+}
+
+#[test]
+#[ignore = "requires a MongoDB server on localhost"]
+fn loads_repeated_references_once_per_reference() {
+    bongo::connect("mongodb://localhost:27017", "bongo_relations").unwrap();
+    let people = vec![
+        Person {
+            _id: ObjectId::new().unwrap(),
+            name: "Ada".to_owned(),
+        },
+        Person {
+            _id: ObjectId::new().unwrap(),
+            name: "Grace".to_owned(),
+        },
+    ];
+    Person::insert_many_sync(&people).unwrap();
+
+    let (a, b) = (people[0]._id.clone(), people[1]._id.clone());
+    let watchers = task(None, vec![a.clone(), b, a]).watchers_sync().unwrap();
+    let names: Vec<_> = watchers.iter().map(|p| p.name.as_str()).collect();
+    assert_eq!(names, vec!["Ada", "Grace", "Ada"]);
+
+    let ids: Vec<_> = people.into_iter().map(|p| p._id).collect();
+    Person::delete_many_sync(bson::doc! {"_id": {"$in": ids}}).unwrap();
+}
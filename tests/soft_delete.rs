@@ -0,0 +1,93 @@
+#![allow(dead_code)]
+
+use bongo::BlockingModel;
+use bson::{doc, oid::ObjectId, UtcDateTime};
+use serde::{Deserialize, Serialize};
+
+#[derive(BlockingModel, Serialize, Deserialize)]
+#[bongo(soft_delete, connection = "soft_delete")]
+struct Invoice {
+    _id: ObjectId,
+    deleted_at: Option<UtcDateTime>,
+}
+
+#[derive(BlockingModel, Serialize, Deserialize)]
+#[bongo(soft_delete(deleted_at = "removed"))]
+struct Contract {
+    _id: ObjectId,
+    #[serde(rename = "removedAt")]
+    removed: Option<UtcDateTime>,
+}
+
+#[derive(BlockingModel, Serialize, Deserialize)]
+struct Receipt {
+    _id: ObjectId,
+}
+
+#[test]
+fn declares_deletion_key() {
+    assert_eq!(Invoice::soft_delete(), Some("deleted_at"));
+    assert_eq!(Contract::soft_delete(), Some("removedAt"));
+    assert_eq!(Receipt::soft_delete(), None);
+}
+
+// Restoring a model which isn't soft deleted never reaches the database.
+#[test]
+fn restoring_hard_deleted_models_does_nothing() {
+    let receipt = Receipt {
+        _id: ObjectId::new().unwrap(),
+    };
+    let result = receipt.restore_sync().unwrap();
+    assert_eq!(result.matched_count, 0);
+    assert_eq!(result.modified_count, 0);
+}
+
+#[test]
+#[ignore = "requires a MongoDB server on localhost"]
+fn writes_skip_deleted_documents() {
+    let _ = bongo::connect_named(
+        "soft_delete",
+        "mongodb://localhost:27017",
+        "bongo_soft_delete",
+    );
+    let invoice = Invoice {
+        _id: ObjectId::new().unwrap(),
+        deleted_at: None,
+    };
+    invoice.save_sync().unwrap();
+    invoice.remove_sync().unwrap();
+    let stamp = Invoice::query()
+        .with_deleted()
+        .filter(invoice.id_query())
+        .first_sync()
+        .unwrap()
+        .unwrap()
+        .deleted_at;
+    assert!(stamp.is_some());
+
+    let update = doc! {"$set": {"total": 1}};
+    let result = Invoice::update_one_sync(invoice.id_query(), update.clone()).unwrap();
+    assert_eq!(result.matched_count, 0);
+    let result = Invoice::query()
+        .filter(invoice.id_query())
+        .update_sync(update)
+        .unwrap();
+    assert_eq!(result.matched_count, 0);
+
+    let result = Invoice::query()
+        .only_deleted()
+        .filter(invoice.id_query())
+        .delete_sync()
+        .unwrap();
+    assert_eq!(result.deleted_count, 0);
+    let stored = Invoice::query()
+        .with_deleted()
+        .filter(invoice.id_query())
+        .first_sync()
+        .unwrap()
+        .unwrap();
+    assert_eq!(stored.deleted_at, stamp);
+
+    assert_eq!(invoice.restore_sync().unwrap().modified_count, 1);
+    invoice.force_remove_sync().unwrap();
+}
@@ -0,0 +1,53 @@
+#![allow(dead_code)]
+
+use bongo::{BlockingModel, Timestamps};
+use bson::{oid::ObjectId, UtcDateTime};
+use serde::{Deserialize, Serialize};
+
+#[derive(BlockingModel, Serialize, Deserialize)]
+#[bongo(timestamps)]
+struct Comment {
+    _id: ObjectId,
+    body: String,
+    created_at: Option<UtcDateTime>,
+    updated_at: Option<UtcDateTime>,
+}
+
+#[derive(BlockingModel, Serialize, Deserialize)]
+#[bongo(timestamps(created_at = "posted", updated_at = "edited"))]
+#[serde(rename_all = "camelCase")]
+struct Post {
+    #[serde(rename = "_id")]
+    id: ObjectId,
+    posted_on: Option<UtcDateTime>,
+    #[serde(rename = "lastEdit")]
+    edited: Option<UtcDateTime>,
+}
+
+#[derive(BlockingModel, Serialize, Deserialize)]
+struct Draft {
+    _id: ObjectId,
+}
+
+#[test]
+fn declares_timestamp_keys() {
+    assert_eq!(
+        Comment::timestamps(),
+        Some(Timestamps {
+            created_at: "created_at",
+            updated_at: "updated_at",
+        })
+    );
+    assert_eq!(Draft::timestamps(), None);
+}
+
+#[test]
+fn maps_timestamp_fields_to_keys() {
+    assert_eq!(
+        Post::timestamps(),
+        Some(Timestamps {
+            created_at: "posted",
+            updated_at: "lastEdit",
+        })
+    );
+}
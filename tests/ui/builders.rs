@@ -0,0 +1,22 @@
+use bongo::{BlockingModel, Schema};
+use serde::{Deserialize, Serialize};
+
+#[derive(BlockingModel, Serialize, Deserialize)]
+struct User {
+    _id: i32,
+}
+
+#[derive(BlockingModel, Serialize, Deserialize)]
+struct Todo {
+    _id: i32,
+    done: bool,
+}
+
+fn mixed_models() -> bongo::Result<()> {
+    User::find_sync(Todo::filter().done().eq(true), None, None)?;
+    User::update_many_sync(User::filter()._id().eq(1), Todo::update().done().set(true))?;
+    User::query().filter(Todo::filter()._id().eq(1)).count_sync()?;
+    Ok(())
+}
+
+fn main() {}
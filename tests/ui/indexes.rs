@@ -0,0 +1,66 @@
+use bongo::BlockingModel;
+
+#[derive(BlockingModel)]
+#[bongo(index(unique))]
+struct NoFields {
+    _id: i32,
+}
+
+#[derive(BlockingModel)]
+#[bongo(index(fields(name), background))]
+struct BadFields {
+    _id: i32,
+    name: String,
+}
+
+#[derive(BlockingModel)]
+#[bongo(index(fields("name"), background))]
+struct UnknownOption {
+    _id: i32,
+    name: String,
+}
+
+#[derive(BlockingModel)]
+struct BadTtl {
+    _id: i32,
+    #[bongo(ttl = "3y")]
+    expires_at: i64,
+}
+
+#[derive(BlockingModel)]
+#[bongo(index(fields("name"), partial_filter = "not a path"))]
+struct BadPartialFilter {
+    _id: i32,
+    name: String,
+}
+
+#[derive(BlockingModel)]
+#[bongo(index(fields("name"), collation(locale)))]
+struct BadCollation {
+    _id: i32,
+    name: String,
+}
+
+#[derive(BlockingModel)]
+struct LongTtl {
+    _id: i32,
+    #[bongo(ttl = "999999999999999w")]
+    expires_at: i64,
+}
+
+#[derive(BlockingModel)]
+struct NegativeTtl {
+    _id: i32,
+    #[bongo(ttl = -60)]
+    expires_at: i64,
+}
+
+#[derive(BlockingModel)]
+#[bongo(index(fields("name"), sparse))]
+struct DuplicateIndex {
+    _id: i32,
+    #[bongo(unique)]
+    name: String,
+}
+
+fn main() {}
@@ -0,0 +1,37 @@
+use bongo::BlockingModel;
+
+#[derive(BlockingModel)]
+#[bongo(colection = "users")]
+struct Typo {
+    _id: i32,
+    #[bongo(uniq, indexed)]
+    email: String,
+}
+
+#[derive(BlockingModel)]
+#[bongo(hooks = "yes", collection)]
+struct Malformed {
+    _id: i32,
+    #[bongo(version(i64))]
+    version: i64,
+}
+
+#[derive(BlockingModel)]
+#[bongo = "users"]
+struct NotAList {
+    _id: i32,
+}
+
+#[derive(BlockingModel)]
+#[bongo("users")]
+struct Literal {
+    _id: i32,
+}
+
+#[derive(BlockingModel)]
+#[bongo(collection = users)]
+struct Unquoted {
+    _id: i32,
+}
+
+fn main() {}
@@ -0,0 +1,23 @@
+use bongo::BlockingModel;
+use serde::{Deserialize, Serialize};
+
+#[derive(BlockingModel, Serialize, Deserialize)]
+#[bongo(referenced_by(Todo, "authr"))]
+struct User {
+    _id: i32,
+}
+
+#[derive(BlockingModel, Serialize, Deserialize)]
+#[bongo(referenced_by(Todo, "not a field"))]
+struct Team {
+    _id: i32,
+}
+
+#[derive(BlockingModel, Serialize, Deserialize)]
+struct Todo {
+    _id: i32,
+    #[bongo(has_one(User))]
+    author: i32,
+}
+
+fn main() {}
@@ -0,0 +1,37 @@
+use bongo::BlockingModel;
+
+#[derive(BlockingModel)]
+struct MissingTarget {
+    _id: i32,
+    #[bongo(has_one())]
+    author: i32,
+}
+
+#[derive(BlockingModel)]
+struct LiteralTarget {
+    _id: i32,
+    #[bongo(has_one("User"))]
+    author: i32,
+}
+
+#[derive(BlockingModel)]
+struct UnquotedGetter {
+    _id: i32,
+    #[bongo(has_many(MissingTarget, tags_sync))]
+    tags: Vec<i32>,
+}
+
+#[derive(BlockingModel)]
+struct TooManyArguments {
+    _id: i32,
+    #[bongo(has_many(MissingTarget, "tags_sync", "tags", "more"))]
+    tags: Vec<i32>,
+}
+
+#[derive(BlockingModel)]
+#[bongo(referenced_by(TooManyArguments))]
+struct MissingField {
+    _id: i32,
+}
+
+fn main() {}
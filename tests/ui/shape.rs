@@ -0,0 +1,22 @@
+use bongo::BlockingModel;
+
+#[derive(BlockingModel)]
+enum Status {
+    Active,
+}
+
+#[derive(BlockingModel)]
+struct Pair(i32, i32);
+
+#[derive(BlockingModel)]
+struct Anonymous {
+    id: i32,
+}
+
+#[derive(BlockingModel)]
+struct Ambiguous {
+    _id: i32,
+    id: i32,
+}
+
+fn main() {}
@@ -0,0 +1,45 @@
+use bongo::BlockingModel;
+
+#[derive(BlockingModel)]
+struct BadRegex {
+    _id: i32,
+    #[bongo(validate(regex = "[a-z"))]
+    name: String,
+}
+
+#[derive(BlockingModel)]
+struct UnknownRule {
+    _id: i32,
+    #[bongo(validate(url))]
+    site: String,
+}
+
+#[derive(BlockingModel)]
+struct BadLength {
+    _id: i32,
+    #[bongo(validate(min_len = "3"))]
+    name: String,
+}
+
+#[derive(BlockingModel)]
+struct BadRange {
+    _id: i32,
+    #[bongo(validate(range(min = "0", step = 2)))]
+    age: i32,
+}
+
+#[derive(BlockingModel)]
+#[bongo(validate_with = "not a path")]
+struct BadValidator {
+    _id: i32,
+}
+
+#[derive(BlockingModel)]
+#[bongo(validate_with_async = "check_owner")]
+struct AsyncValidator {
+    _id: i32,
+    #[bongo(validate_with_async = "check_name")]
+    name: String,
+}
+
+fn main() {}
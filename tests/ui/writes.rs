@@ -0,0 +1,31 @@
+use bongo::BlockingModel;
+
+#[derive(BlockingModel)]
+#[bongo(timestamps(created = "created_at"))]
+struct BadTimestamps {
+    _id: i32,
+}
+
+#[derive(BlockingModel)]
+#[bongo(soft_delete(deleted_at = 1))]
+struct BadSoftDelete {
+    _id: i32,
+}
+
+#[derive(BlockingModel)]
+struct TwoVersions {
+    _id: i32,
+    #[bongo(version)]
+    version: i64,
+    #[bongo(version)]
+    revision: i64,
+}
+
+#[derive(BlockingModel)]
+struct FloatVersion {
+    _id: i32,
+    #[bongo(version)]
+    version: f64,
+}
+
+fn main() {}
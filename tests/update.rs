@@ -0,0 +1,85 @@
+#![allow(dead_code)]
+
+use bongo::{BlockingModel, Error, Schema, Update};
+use bson::{doc, oid::ObjectId, Document};
+use serde::{Deserialize, Serialize};
+
+#[derive(BlockingModel, Serialize, Deserialize)]
+struct Article {
+    _id: ObjectId,
+    title: String,
+    #[serde(rename = "viewCount")]
+    views: i64,
+    score: f64,
+    tags: Vec<String>,
+    draft: Option<bool>,
+    downloads: u64,
+}
+
+fn document(update: <Article as Schema>::Update) -> Document {
+    Update::from(update).into_document().unwrap()
+}
+
+#[test]
+fn groups_operators() {
+    let update = document(
+        Article::update()
+            .title()
+            .set("Hello")
+            .views()
+            .inc(1)
+            .score()
+            .mul(1.5)
+            .draft()
+            .unset()
+            .tags()
+            .add_to_set("news"),
+    );
+    assert_eq!(
+        update,
+        doc! {
+            "$set": {"title": "Hello"},
+            "$inc": {"viewCount": 1i64},
+            "$mul": {"score": 1.5},
+            "$unset": {"draft": ""},
+            "$addToSet": {"tags": "news"},
+        }
+    );
+}
+
+#[test]
+fn merges_fields_of_same_operator() {
+    let update = document(
+        Article::update()
+            .title()
+            .set("Hello")
+            .draft()
+            .set(false)
+            .tags()
+            .push_all(vec!["a", "b"]),
+    );
+    assert_eq!(
+        update,
+        doc! {
+            "$set": {"title": "Hello", "draft": false},
+            "$push": {"tags": {"$each": ["a", "b"]}},
+        }
+    );
+}
+
+#[test]
+fn reports_unencodable_values() {
+    let update = Update::from(Article::update().title().set("Hello").downloads().set(1u64));
+    assert!(matches!(update.into_document(), Err(Error::BsonEncode(_))));
+}
+
+fn publish_sync(id: ObjectId) -> bongo::Result<Option<Article>> {
+    Article::update_one_sync(
+        Article::filter().title().eq("draft"),
+        Article::update().draft().set(false),
+    )?;
+    Article::find_one_and_update_sync(
+        Article::filter()._id().eq(id),
+        Article::update().views().inc(1),
+    )
+}
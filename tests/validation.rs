@@ -0,0 +1,139 @@
+#![allow(dead_code)]
+
+use bongo::{BlockingModel, Error, Model, ValidationError, ValidationErrors};
+use bson::oid::ObjectId;
+use serde::{Deserialize, Serialize};
+
+#[derive(BlockingModel, Serialize, Deserialize)]
+struct Signup {
+    _id: ObjectId,
+    #[bongo(validate(min_len = 3, max_len = 8, regex = "^[a-z]+$"))]
+    username: String,
+    #[bongo(validate(email))]
+    #[serde(rename = "mail")]
+    email: String,
+    #[bongo(validate(range(min = 0, max = 150)))]
+    age: i32,
+    #[bongo(validate(range(min = -1, max = 1)))]
+    score: f64,
+    #[bongo(validate(max_len = 2))]
+    tags: Option<Vec<String>>,
+}
+
+#[derive(Model, Serialize, Deserialize)]
+#[bongo(validate_with = "check_dates", validate_with_async = "check_room_free")]
+struct Booking {
+    _id: ObjectId,
+    #[bongo(validate_with = "check_room")]
+    room: String,
+    start: i64,
+    end: i64,
+}
+
+fn check_dates(booking: &Booking, errors: &mut ValidationErrors) {
+    if booking.end <= booking.start {
+        errors.add("end", "must be after start");
+    }
+}
+
+fn check_room(room: &str) -> Result<(), &'static str> {
+    if room.starts_with('R') {
+        Ok(())
+    } else {
+        Err("must be a room number")
+    }
+}
+
+async fn check_room_free(booking: &Booking, errors: &mut ValidationErrors) {
+    if booking.room == "R404" {
+        errors.add("room", "is already booked");
+    }
+}
+
+fn valid() -> Signup {
+    Signup {
+        _id: ObjectId::new().unwrap(),
+        username: "bob".to_owned(),
+        email: "bob@example.com".to_owned(),
+        age: 42,
+        score: 0.5,
+        tags: None,
+    }
+}
+
+fn failing_fields(signup: &Signup) -> Vec<String> {
+    match signup.validate() {
+        Ok(()) => Vec::new(),
+        Err(Error::Validation(e)) => e.errors.into_iter().map(|e| e.field).collect(),
+        Err(e) => panic!("unexpected error {}", e),
+    }
+}
+
+#[test]
+fn accepts_valid_models() {
+    assert!(valid().validate().is_ok());
+}
+
+#[test]
+fn reports_every_failing_field() {
+    let signup = Signup {
+        username: "Bobby Tables".to_owned(),
+        email: "bob@localhost".to_owned(),
+        age: 200,
+        score: -1.5,
+        tags: Some(vec!["a".to_owned(), "b".to_owned(), "c".to_owned()]),
+        ..valid()
+    };
+    assert_eq!(
+        failing_fields(&signup),
+        vec!["username", "username", "mail", "age", "score", "tags"]
+    );
+
+    match signup.validate() {
+        Err(Error::Validation(e)) => assert_eq!(
+            e.errors[3],
+            ValidationError {
+                field: "age".to_owned(),
+                message: "must be between 0 and 150".to_owned(),
+            }
+        ),
+        _ => unreachable!(),
+    }
+}
+
+#[test]
+fn runs_custom_validators() {
+    let booking = Booking {
+        _id: ObjectId::new().unwrap(),
+        room: "404".to_owned(),
+        start: 10,
+        end: 5,
+    };
+    match booking.validate() {
+        Err(Error::Validation(e)) => assert_eq!(
+            e.errors,
+            vec![
+                ValidationError {
+                    field: "room".to_owned(),
+                    message: "must be a room number".to_owned(),
+                },
+                ValidationError {
+                    field: "end".to_owned(),
+                    message: "must be after start".to_owned(),
+                },
+            ]
+        ),
+        _ => panic!("expected a validation error"),
+    }
+
+    let booking = Booking {
+        room: "R404".to_owned(),
+        end: 20,
+        ..booking
+    };
+    assert!(booking.validate().is_ok());
+    match futures::executor::block_on(booking.validate_async()) {
+        Err(Error::Validation(e)) => assert_eq!(e.to_string(), "room is already booked"),
+        _ => panic!("expected a validation error"),
+    }
+}
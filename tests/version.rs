@@ -0,0 +1,126 @@
+#![allow(dead_code)]
+
+use bongo::{BlockingModel, Error};
+use bson::{doc, oid::ObjectId};
+use serde::{Deserialize, Serialize};
+
+#[derive(BlockingModel, Serialize, Deserialize)]
+struct Page {
+    _id: ObjectId,
+    body: String,
+    #[bongo(version)]
+    #[serde(rename = "__v")]
+    revision: i32,
+}
+
+#[derive(BlockingModel, Serialize, Deserialize)]
+struct Note {
+    _id: ObjectId,
+    #[bongo(version)]
+    version: i64,
+}
+
+#[derive(BlockingModel, Serialize, Deserialize)]
+#[bongo(connection = "versions")]
+struct Account {
+    _id: ObjectId,
+    #[bongo(unique)]
+    email: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    nickname: Option<String>,
+    #[bongo(version)]
+    version: i64,
+}
+
+fn connect() {
+    let _ = bongo::connect_named("versions", "mongodb://localhost:27017", "bongo_versions");
+}
+
+fn account(email: &str) -> Account {
+    Account {
+        _id: ObjectId::new().unwrap(),
+        email: email.to_owned(),
+        nickname: Some("nick".to_owned()),
+        version: 0,
+    }
+}
+
+#[test]
+fn exposes_version_field() {
+    let mut page = Page {
+        _id: ObjectId::new().unwrap(),
+        body: "Hello".to_owned(),
+        revision: 3,
+    };
+    assert_eq!(page.version(), Some(("__v", 3)));
+
+    page.set_version(4);
+    assert_eq!(page.revision, 4);
+
+    let note = Note {
+        _id: ObjectId::new().unwrap(),
+        version: 0,
+    };
+    assert_eq!(note.version(), Some(("version", 0)));
+}
+
+// The version check comes first, so this never needs a connection.
+#[test]
+fn rejects_unversioned_saves_of_versioned_models() {
+    let account = account("unversioned@localhost");
+    assert!(matches!(account.save_sync(), Err(Error::Versioned(_))));
+}
+
+#[test]
+#[ignore = "requires a MongoDB server on localhost"]
+fn rejects_saves_over_newer_versions() {
+    connect();
+    let mut account = account("conflict@localhost");
+    account.save_versioned_sync().unwrap();
+    assert_eq!(account.version, 1);
+
+    let mut stale = Account {
+        nickname: None,
+        ..Account::find_one_sync(account.id_query()).unwrap().unwrap()
+    };
+    account.save_versioned_sync().unwrap();
+    assert_eq!(account.version, 2);
+    assert!(matches!(
+        stale.save_versioned_sync(),
+        Err(Error::VersionConflict(_))
+    ));
+
+    let stored = Account::find_one_sync(account.id_query()).unwrap().unwrap();
+    assert_eq!(stored.version, 2);
+    assert_eq!(stored.nickname.as_deref(), Some("nick"));
+
+    let mut fresh = Account {
+        nickname: None,
+        ..stored
+    };
+    fresh.save_versioned_sync().unwrap();
+    let stored = Account::find_one_sync(fresh.id_query()).unwrap().unwrap();
+    assert_eq!(stored.version, 3);
+    assert_eq!(stored.nickname, None);
+
+    Account::delete_many_sync(doc! {"_id": account._id}).unwrap();
+}
+
+#[test]
+#[ignore = "requires a MongoDB server on localhost"]
+fn reports_unique_violations_on_versioned_saves() {
+    connect();
+    Account::sync_indexes_sync(false).unwrap();
+    let mut first = account("unique@localhost");
+    first.save_versioned_sync().unwrap();
+
+    let mut second = account("other@localhost");
+    second.save_versioned_sync().unwrap();
+    second.email = first.email.clone();
+    assert!(matches!(
+        second.save_versioned_sync(),
+        Err(Error::MongoDb(_))
+    ));
+
+    Account::delete_many_sync(doc! {"_id": {"$in": [first._id, second._id]}}).unwrap();
+}